@@ -2,15 +2,17 @@
 
 extern crate test;
 
+use big_numbers::backend::{LimbBackend, Native, Portable};
 use big_numbers::int::Int;
+use big_numbers::workspace::Workspace;
 use big_numbers::IntLimb;
 use test::Bencher;
 
 #[bench]
 fn add_bench(ben: &mut Bencher) {
     fn n_additions(a: &Int, b: &Int, n: i32) {
-        for i in 0..n {
-            a + b;
+        for _ in 0..n {
+            let _ = a + b;
         }
     }
     let a = Int::from((10, "7799476601844501102305401045355421728095916034908428628939400533506264447916217201951091768726426832809611399717962665235527581632337561345565876731297655978330048122528263802617579145330884277033721126690740252033624739555953519007966677932275408332444814480393921564897483434863147266404177295193463673134437480524283120048813928094190619063565741597202240555527704710095322108654955875834323969699677552327931114764233304498189385946028900821614101043955147837273960959858693159104052474052545966294837656123236171926462333281375840609496630746170728210589622812491257705367200333695787186777592776972582151020517514520080936119044661448381097757958480213763680841284503840223740406573893741569197709449734029759408021750362837503105517427901877382919632472001817020899894169610976558724670463100453925758713343098561069387092297405305057074020945281273407933123649935700085015071973281079057640444207234665373090020261758098028152107297100313596611385516490635734090800357133290059024369672834651"));
@@ -22,8 +24,8 @@ fn add_bench(ben: &mut Bencher) {
 #[bench]
 fn sub_bench(ben: &mut Bencher) {
     fn n_subs(a: &Int, b: &Int, n: i32) {
-        for i in 0..n {
-            a - b;
+        for _ in 0..n {
+            let _ = a - b;
         }
     }
     let a = Int::from((10, "7799476601844501102305401045355421728095916034908428628939400533506264447916217201951091768726426832809611399717962665235527581632337561345565876731297655978330048122528263802617579145330884277033721126690740252033624739555953519007966677932275408332444814480393921564897483434863147266404177295193463673134437480524283120048813928094190619063565741597202240555527704710095322108654955875834323969699677552327931114764233304498189385946028900821614101043955147837273960959858693159104052474052545966294837656123236171926462333281375840609496630746170728210589622812491257705367200333695787186777592776972582151020517514520080936119044661448381097757958480213763680841284503840223740406573893741569197709449734029759408021750362837503105517427901877382919632472001817020899894169610976558724670463100453925758713343098561069387092297405305057074020945281273407933123649935700085015071973281079057640444207234665373090020261758098028152107297100313596611385516490635734090800357133290059024369672834651"));
@@ -35,8 +37,8 @@ fn sub_bench(ben: &mut Bencher) {
 #[bench]
 fn mul_bench(ben: &mut Bencher) {
     fn n_muls(a: &Int, b: &Int, n: i32) {
-        for i in 0..n {
-            a * b;
+        for _ in 0..n {
+            let _ = a * b;
         }
     }
     let a = Int::from((10, "7799476601844501102305401045355421728095916034908428628939400533506264447916217201951091768726426832809611399717962665235527581632337561345565876731297655978330048122528263802617579145330884277033721126690740252033624739555953519007966677932275408332444814480393921564897483434863147266404177295193463673134437480524283120048813928094190619063565741597202240555527704710095322108654955875834323969699677552327931114764233304498189385946028900821614101043955147837273960959858693159104052474052545966294837656123236171926462333281375840609496630746170728210589622812491257705367200333695787186777592776972582151020517514520080936119044661448381097757958480213763680841284503840223740406573893741569197709449734029759408021750362837503105517427901877382919632472001817020899894169610976558724670463100453925758713343098561069387092297405305057074020945281273407933123649935700085015071973281079057640444207234665373090020261758098028152107297100313596611385516490635734090800357133290059024369672834651"));
@@ -48,8 +50,8 @@ fn mul_bench(ben: &mut Bencher) {
 #[bench]
 fn div_bench(ben: &mut Bencher) {
     fn n_divs(a: &Int, b: &Int, n: i32) {
-        for i in 0..n {
-            a / b;
+        for _ in 0..n {
+            let _ = a / b;
         }
     }
     let a = Int::from((10, "7799476601844501102305401045355421728095916034908428628939400533506264447916217201951091768726426832809611399717962665235527581632337561345565876731297655978330048122528263802617579145330884277033721126690740252033624739555953519007966677932275408332444814480393921564897483434863147266404177295193463673134437480524283120048813928094190619063565741597202240555527704710095322108654955875834323969699677552327931114764233304498189385946028900821614101043955147837273960959858693159104052474052545966294837656123236171926462333281375840609496630746170728210589622812491257705367200333695787186777592776972582151020517514520080936119044661448381097757958480213763680841284503840223740406573893741569197709449734029759408021750362837503105517427901877382919632472001817020899894169610976558724670463100453925758713343098561069387092297405305057074020945281273407933123649935700085015071973281079057640444207234665373090020261758098028152107297100313596611385516490635734090800357133290059024369672834651"));
@@ -61,8 +63,8 @@ fn div_bench(ben: &mut Bencher) {
 #[bench]
 fn rem_bench(ben: &mut Bencher) {
     fn n_rems(a: &Int, b: &Int, n: i32) {
-        for i in 0..n {
-            a % b;
+        for _ in 0..n {
+            let _ = a % b;
         }
     }
     let a = Int::from((10, "7799476601844501102305401045355421728095916034908428628939400533506264447916217201951091768726426832809611399717962665235527581632337561345565876731297655978330048122528263802617579145330884277033721126690740252033624739555953519007966677932275408332444814480393921564897483434863147266404177295193463673134437480524283120048813928094190619063565741597202240555527704710095322108654955875834323969699677552327931114764233304498189385946028900821614101043955147837273960959858693159104052474052545966294837656123236171926462333281375840609496630746170728210589622812491257705367200333695787186777592776972582151020517514520080936119044661448381097757958480213763680841284503840223740406573893741569197709449734029759408021750362837503105517427901877382919632472001817020899894169610976558724670463100453925758713343098561069387092297405305057074020945281273407933123649935700085015071973281079057640444207234665373090020261758098028152107297100313596611385516490635734090800357133290059024369672834651"));
@@ -83,3 +85,103 @@ fn factorial(ben: &mut Bencher) {
 
     ben.iter(|| fact(5000));
 }
+
+fn mul_kernel<B: LimbBackend>(ben: &mut Bencher) {
+    let a: Vec<IntLimb> = (1..=24)
+        .map(|i: IntLimb| i.wrapping_mul(0x9e3779b97f4a7c15))
+        .collect();
+    let b: Vec<IntLimb> = (1..=24)
+        .map(|i: IntLimb| (!i).wrapping_mul(0x2545f4914f6cdd1d))
+        .collect();
+    let mut dest = vec![0; a.len() + b.len()];
+
+    ben.iter(|| {
+        dest.iter_mut().for_each(|d| *d = 0);
+        B::mul(&a, &b, &mut dest);
+    });
+}
+
+#[bench]
+fn native_mul_kernel(ben: &mut Bencher) {
+    mul_kernel::<Native>(ben);
+}
+
+#[bench]
+fn portable_mul_kernel(ben: &mut Bencher) {
+    mul_kernel::<Portable>(ben);
+}
+
+// Dense pseudo-random operand of `limbs` 64-bit words, so the benchmarks below run in the
+// range of the algorithm they are named after.
+fn random_int(limbs: usize, mut state: u64) -> Int {
+    let digits: String = (0..limbs)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            format!("{:016x}", state)
+        })
+        .collect();
+
+    Int::from((16, digits.as_str()))
+}
+
+fn mul_with_workspace(ben: &mut Bencher, limbs: usize, reused: bool) {
+    let a = random_int(limbs, 0x9e3779b97f4a7c15);
+    let b = random_int(limbs, 0x2545f4914f6cdd1d);
+    let mut workspace = Workspace::new();
+
+    if reused {
+        ben.iter(|| a.mul_with(&b, &mut workspace));
+    } else {
+        ben.iter(|| &a * &b);
+    }
+}
+
+#[bench]
+fn mul_without_workspace_karatsuba(ben: &mut Bencher) {
+    mul_with_workspace(ben, 120, false);
+}
+
+#[bench]
+fn mul_without_workspace_toom3(ben: &mut Bencher) {
+    mul_with_workspace(ben, 600, false);
+}
+
+#[bench]
+fn mul_with_workspace_karatsuba(ben: &mut Bencher) {
+    mul_with_workspace(ben, 120, true);
+}
+
+#[bench]
+fn mul_with_workspace_toom3(ben: &mut Bencher) {
+    mul_with_workspace(ben, 600, true);
+}
+
+fn mul_backend<B: LimbBackend>(ben: &mut Bencher, limbs: usize) {
+    let a = random_int(limbs, 0x9e3779b97f4a7c15);
+    let b = random_int(limbs, 0x2545f4914f6cdd1d);
+    let mut workspace = Workspace::new();
+
+    ben.iter(|| a.mul_with_backend::<B>(&b, &mut workspace));
+}
+
+#[bench]
+fn native_mul_backend_karatsuba(ben: &mut Bencher) {
+    mul_backend::<Native>(ben, 120);
+}
+
+#[bench]
+fn portable_mul_backend_karatsuba(ben: &mut Bencher) {
+    mul_backend::<Portable>(ben, 120);
+}
+
+#[bench]
+fn native_mul_backend_toom3(ben: &mut Bencher) {
+    mul_backend::<Native>(ben, 600);
+}
+
+#[bench]
+fn portable_mul_backend_toom3(ben: &mut Bencher) {
+    mul_backend::<Portable>(ben, 600);
+}
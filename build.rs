@@ -1,27 +1,55 @@
 extern crate cc;
 
+use std::env;
+
+const KERNELS: [&str; 13] = [
+    "add",
+    "addmul",
+    "add_const",
+    "cmp",
+    "div_const",
+    "sub",
+    "sub_const",
+    "submul",
+    "mul",
+    "mul_const",
+    "shl",
+    "shr",
+    "sqr",
+];
+
+// BMI2/ADX variants picked at runtime by asm_ops::dispatch.
+const X86_64_ADX_KERNELS: [&str; 4] = ["add_adx", "sub_adx", "mul_adx", "mul_const_adx"];
+
 fn main() -> Result<(), String> {
-    let asm_srcs = if cfg!(target_arch = "x86_64") {
-        [
-            "src/asm_ops/asm/x86_64/add.s",
-            "src/asm_ops/asm/x86_64/add_const.s",
-            "src/asm_ops/asm/x86_64/cmp.s",
-            "src/asm_ops/asm/x86_64/div_const.s",
-            "src/asm_ops/asm/x86_64/sub.s",
-            "src/asm_ops/asm/x86_64/sub_const.s",
-            "src/asm_ops/asm/x86_64/mul.s",
-            "src/asm_ops/asm/x86_64/mul_const.s",
-        ]
-        .to_vec()
-    } else {
-        [].to_vec()
+    // cfg!(target_arch) would be the host's, the target comes from cargo.
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").map_err(|e| e.to_string())?;
+    let portable = env::var_os("CARGO_FEATURE_PORTABLE").is_some();
+
+    // Set below when the assembly kernels are linked, asm_ops uses the pure Rust ones otherwise.
+    println!("cargo:rustc-check-cfg=cfg(asm_kernels)");
+
+    // Every assembly backend has the same kernels under the same file names.
+    let kernels = match target_arch.as_str() {
+        "x86_64" => [&KERNELS[..], &X86_64_ADX_KERNELS[..]].concat(),
+        "aarch64" => KERNELS.to_vec(),
+        _ => Vec::new(),
     };
+    let asm_srcs: Vec<String> = kernels
+        .iter()
+        .map(|kernel| format!("src/asm_ops/asm/{}/{}.s", target_arch, kernel))
+        .collect();
+
+    if portable || asm_srcs.is_empty() {
+        return Ok(());
+    }
 
-    if asm_srcs.is_empty() {
-        return Err("Unsupported architecture".to_string());
+    for src in asm_srcs.iter() {
+        println!("cargo:rerun-if-changed={}", src);
     }
 
     cc::Build::new().files(asm_srcs).compile("libasm.a");
+    println!("cargo:rustc-cfg=asm_kernels");
 
     Ok(())
 }
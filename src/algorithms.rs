@@ -1,121 +1,983 @@
-use crate::asm_ops::wrapped_ops;
-use crate::asm_ops::{add_two_slices, sub_two_slices};
-use crate::utils::trim_zeros;
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+
+use crate::backend::LimbBackend;
+use crate::base_ops::{add, div, mul_limb, mul_with, sub, sub_limb};
+use crate::bit_ops::{shl, shr};
+use crate::utils::{cmp_repr, trim_zeros, trimmed};
+use crate::workspace::Workspace;
 use crate::IntLimb;
 
-type MulFunction = fn(&[IntLimb], &[IntLimb]) -> Vec<IntLimb>;
+type MulFunction = fn(&[IntLimb], &[IntLimb], &mut Workspace) -> Vec<IntLimb>;
+type SqrFunction = fn(&[IntLimb], &mut Workspace) -> Vec<IntLimb>;
+type DivFunction = fn(&[IntLimb], &[IntLimb], &mut Workspace) -> (Vec<IntLimb>, Vec<IntLimb>);
 
-#[inline]
-fn add_helper(dest: &mut [IntLimb], a: &[IntLimb], l: IntLimb, offset: isize) {
-    if a.is_empty() {
+// dest += a with the carry propagated through dest, the sum has to fit in dest.
+fn add_in_place<B: LimbBackend>(dest: &mut [IntLimb], a: &[IntLimb]) {
+    let k = a.len();
+
+    if k == 0 {
         return;
     }
-    unsafe {
-        if l > a.len() as IntLimb {
-            add_two_slices(
-                dest.as_ptr().offset(offset),
-                a.as_ptr(),
-                dest.as_mut_ptr().offset(offset),
-                a.len() as IntLimb,
-                a.len() as IntLimb,
-            );
-        } else {
-            add_two_slices(
-                a.as_ptr(),
-                dest.as_ptr().offset(offset),
-                dest.as_mut_ptr().offset(offset),
-                a.len() as IntLimb,
-                a.len() as IntLimb,
-            );
+
+    if k < dest.len() {
+        // The kernel writes its carry over dest[k] instead of adding it.
+        let top = dest[k];
+        B::add_assign(&mut dest[..=k], a);
+        let carry = std::mem::replace(&mut dest[k], top);
+        if carry != 0 {
+            B::add_limb(&mut dest[k..], carry);
         }
+    } else {
+        // Nothing carries out of the top limb, it is added separately.
+        let (top, a_top) = (dest[k - 1], a[k - 1]);
+        let carry = if k > 1 {
+            B::add_assign(dest, &a[..k - 1]);
+            dest[k - 1]
+        } else {
+            0
+        };
+        dest[k - 1] = top.wrapping_add(a_top).wrapping_add(carry);
+    }
+}
+
+// Upper bound on the scratch of `karatsuba`. A level takes at most 2 max(n, m) + 6 limbs
+// and recurses on operands of at most half of that plus one.
+pub(crate) fn karatsuba_scratch_size(n: usize, m: usize, threshold: usize) -> usize {
+    let mut n = usize::max(n, m);
+    let mut size = 0;
+
+    while n > threshold {
+        size += 2 * n + 6;
+        n = n - n / 2 + 1;
     }
+    size
 }
 
-pub(crate) fn karatsuba(
+// dest = a * b, overwriting `a.len() + b.len()` limbs of dest. `scratch` has to hold
+// `karatsuba_scratch_size` limbs and `threshold` has to be at least 3 for the halves to
+// shrink.
+pub(crate) fn karatsuba<B: LimbBackend>(
     a: &[IntLimb],
     b: &[IntLimb],
+    dest: &mut [IntLimb],
     threshold: usize,
+    scratch: &mut [IntLimb],
+) {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let (n, m) = (a.len(), b.len());
+    let dest = &mut dest[..n + m];
+
+    if m <= threshold {
+        dest.fill(0);
+        if m > 0 {
+            B::mul(a, b, dest);
+        }
+        return;
+    }
+
+    let l = n / 2;
+    let (a0, a1) = a.split_at(l);
+
+    // Only a is split, dest = a0 b + a1 b B^l.
+    if m <= l {
+        let (t, scratch) = scratch.split_at_mut(n - l + m);
+        karatsuba::<B>(a0, b, &mut dest[..l + m], threshold, scratch);
+        dest[l + m..].fill(0);
+        karatsuba::<B>(a1, b, t, threshold, scratch);
+        add_in_place::<B>(&mut dest[l..], t);
+        return;
+    }
+
+    let (b0, b1) = b.split_at(l);
+    let (sa, scratch) = scratch.split_at_mut(n - l + 1);
+    let (sb, scratch) = scratch.split_at_mut(usize::max(l, m - l) + 1);
+    let (z1, scratch) = scratch.split_at_mut(sa.len() + sb.len());
+
+    // z0 = a0 b0 and z2 = a1 b1 go straight to their places in dest.
+    karatsuba::<B>(a0, b0, &mut dest[..2 * l], threshold, scratch);
+    karatsuba::<B>(a1, b1, &mut dest[2 * l..], threshold, scratch);
+
+    // (a0 + a1)(b0 + b1) - z0 - z2 = a0 b1 + a1 b0
+    B::add(a0, a1, sa);
+    B::add(b0, b1, sb);
+    karatsuba::<B>(sa, sb, z1, threshold, scratch);
+    B::sub_assign(z1, &dest[..2 * l]);
+    B::sub_assign(z1, &dest[2 * l..]);
+
+    add_in_place::<B>(&mut dest[l..], trimmed(z1));
+}
+
+// Upper bound on the scratch of `karatsuba_sqr`. A level takes 3 (n - n / 2 + 1) limbs
+// for a0 + a1 and its square, and recurses on that sum.
+pub(crate) fn karatsuba_sqr_scratch_size(n: usize, threshold: usize) -> usize {
+    let mut n = n;
+    let mut size = 0;
+
+    while n > threshold {
+        n = n - n / 2 + 1;
+        size += 3 * n;
+    }
+    size
+}
+
+// dest = a * a over `2 * a.len()` limbs, Karatsuba where all three half size products are
+// squares. `scratch` has to hold `karatsuba_sqr_scratch_size` limbs and `threshold` has to
+// be at least 3 for the halves to shrink.
+pub(crate) fn karatsuba_sqr<B: LimbBackend>(
+    a: &[IntLimb],
+    dest: &mut [IntLimb],
+    threshold: usize,
+    scratch: &mut [IntLimb],
+) {
+    let n = a.len();
+    let dest = &mut dest[..2 * n];
+
+    if n <= threshold {
+        if n > 0 {
+            B::sqr(a, dest);
+        }
+        return;
+    }
+
+    let l = n / 2;
+    let (a0, a1) = a.split_at(l);
+    let (sa, scratch) = scratch.split_at_mut(n - l + 1);
+    let (z1, scratch) = scratch.split_at_mut(2 * sa.len());
+
+    // z0 = a0^2 and z2 = a1^2 go straight to their places in dest.
+    karatsuba_sqr::<B>(a0, &mut dest[..2 * l], threshold, scratch);
+    karatsuba_sqr::<B>(a1, &mut dest[2 * l..], threshold, scratch);
+
+    // (a0 + a1)^2 - a0^2 - a1^2 = 2 a0 a1
+    B::add(a0, a1, sa);
+    karatsuba_sqr::<B>(sa, z1, threshold, scratch);
+    B::sub_assign(z1, &dest[..2 * l]);
+    B::sub_assign(z1, &dest[2 * l..]);
+
+    add_in_place::<B>(&mut dest[l..], trimmed(z1));
+}
+
+// Sign-magnitude value for the Toom evaluations and interpolation, which can go negative.
+struct Signed<B> {
+    sign: i8,
+    repr: Vec<IntLimb>,
+    backend: PhantomData<B>,
+}
+
+// Derived Clone would require `B: Clone`.
+impl<B> Clone for Signed<B> {
+    fn clone(&self) -> Self {
+        Signed::new(self.sign, self.repr.clone())
+    }
+}
+
+impl<B> Signed<B> {
+    fn new(sign: i8, repr: Vec<IntLimb>) -> Self {
+        Signed {
+            sign,
+            repr,
+            backend: PhantomData,
+        }
+    }
+
+    fn from_repr(mut repr: Vec<IntLimb>) -> Self {
+        trim_zeros(&mut repr);
+        let sign = if repr.is_empty() { 0 } else { 1 };
+        Signed::new(sign, repr)
+    }
+}
+
+impl<B: LimbBackend> Signed<B> {
+    fn add(&self, other: &Self) -> Self {
+        self.add_signed(other.sign, &other.repr)
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        self.add_signed(-other.sign, &other.repr)
+    }
+
+    fn add_signed(&self, sign: i8, repr: &[IntLimb]) -> Self {
+        if sign == 0 {
+            return self.clone();
+        }
+        if self.sign == 0 {
+            return Signed::new(sign, repr.to_vec());
+        }
+
+        if self.sign == sign {
+            Signed::new(sign, add::<B>(&self.repr, repr))
+        } else {
+            let (s, repr) = sub::<B>(&self.repr, repr);
+            Signed::new(s * self.sign, repr)
+        }
+    }
+
+    fn mul(&self, other: &Self, mul_function: MulFunction, workspace: &mut Workspace) -> Self {
+        if self.sign == 0 || other.sign == 0 {
+            return Signed::from_repr(Vec::new());
+        }
+
+        Signed::new(
+            self.sign * other.sign,
+            mul_function(&self.repr, &other.repr, workspace),
+        )
+    }
+
+    fn sqr(&self, sqr_function: SqrFunction, workspace: &mut Workspace) -> Self {
+        Signed::from_repr(sqr_function(&self.repr, workspace))
+    }
+
+    fn mul_limb(&self, c: IntLimb) -> Self {
+        Signed::new(self.sign, mul_limb::<B>(&self.repr, c))
+    }
+
+    fn shl(&self, bits: usize) -> Self {
+        Signed::new(self.sign, shl::<B>(&self.repr, bits))
+    }
+
+    // The interpolation only ever divides values that are exact multiples.
+    fn div_exact(&self, c: IntLimb) -> Self {
+        let repr = if c.is_power_of_two() {
+            shr::<B>(&self.repr, c.trailing_zeros() as usize).0
+        } else {
+            div::<B>(&self.repr, &[c]).unwrap().0
+        };
+
+        Signed::new(self.sign, repr)
+    }
+}
+
+// a * b for a shorter operand that would leave the top Toom part of b empty: slices a
+// into b.len() sized parts and multiplies each with b, like the unbalanced Karatsuba.
+fn mul_sliced<B: LimbBackend>(
+    a: &[IntLimb],
+    b: &[IntLimb],
+    mul_function: MulFunction,
+    workspace: &mut Workspace,
+) -> Vec<IntLimb> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let m = b.len();
+    let mut dest = vec![0; a.len() + m];
+
+    for (i, part) in a.chunks(m).enumerate() {
+        add_in_place::<B>(&mut dest[i * m..], &mul_function(part, b, workspace));
+    }
+
+    trim_zeros(&mut dest);
+    dest
+}
+
+fn split<B: LimbBackend>(a: &[IntLimb], parts: usize, l: usize) -> Vec<Signed<B>> {
+    (0..parts)
+        .map(|i| {
+            let (start, end) = (usize::min(i * l, a.len()), usize::min((i + 1) * l, a.len()));
+            Signed::<B>::from_repr(a[start..end].to_vec())
+        })
+        .collect()
+}
+
+// Sums `coefficients[i] * B^(i * l)`, where B is the limb base.
+fn recompose<B: LimbBackend>(coefficients: &[Signed<B>], l: usize, len: usize) -> Vec<IntLimb> {
+    let mut dest = vec![0; len + 1];
+
+    for (i, c) in coefficients.iter().enumerate() {
+        if c.sign == 0 {
+            continue;
+        }
+        debug_assert!(c.sign > 0);
+
+        B::add_assign(&mut dest[i * l..], &c.repr);
+    }
+
+    trim_zeros(&mut dest);
+    dest
+}
+
+// Bodrato's evaluation at 0, 1, -1, -2 and infinity.
+fn toom3_evaluate<B: LimbBackend>(x: &[IntLimb], l: usize) -> Vec<Signed<B>> {
+    let p = split::<B>(x, 3, l);
+
+    let p0 = p[0].add(&p[2]);
+    let p1 = p0.add(&p[1]);
+    let pm1 = p0.sub(&p[1]);
+    let pm2 = pm1.add(&p[2]).shl(1).sub(&p[0]);
+
+    vec![p[0].clone(), p1, pm1, pm2, p[2].clone()]
+}
+
+fn toom3_interpolate<B: LimbBackend>(w: &[Signed<B>], l: usize, len: usize) -> Vec<IntLimb> {
+    let (w0, w1, wm1, wm2, winf) = (&w[0], &w[1], &w[2], &w[3], &w[4]);
+
+    let r3 = wm2.sub(w1).div_exact(3);
+    let r1 = w1.sub(wm1).div_exact(2);
+    let r2 = wm1.sub(w0);
+    let r3 = r2.sub(&r3).div_exact(2).add(&winf.shl(1));
+    let r2 = r2.add(&r1).sub(winf);
+    let r1 = r1.sub(&r3);
+
+    recompose::<B>(&[w0.clone(), r1, r2, r3, winf.clone()], l, len)
+}
+
+pub(crate) fn toom3<B: LimbBackend>(
+    a: &[IntLimb],
+    b: &[IntLimb],
     mul_function: MulFunction,
+    workspace: &mut Workspace,
 ) -> Vec<IntLimb> {
     let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 {
+        return Vec::new();
+    }
 
-    if n <= threshold || m <= threshold {
-        return mul_function(&a, &b);
+    let l = usize::max(n, m).div_ceil(3);
+    if usize::min(n, m) <= 2 * l {
+        return mul_sliced::<B>(a, b, mul_function, workspace);
     }
 
-    let l = if n > m { n / 2 } else { m / 2 };
+    let (pa, pb) = (toom3_evaluate::<B>(a, l), toom3_evaluate::<B>(b, l));
+    let w: Vec<Signed<B>> = pa
+        .iter()
+        .zip(pb.iter())
+        .map(|(x, y)| x.mul(y, mul_function, workspace))
+        .collect();
 
-    let mut dest = vec![0; 6 * l + 1];
+    toom3_interpolate::<B>(&w, l, n + m)
+}
 
-    let (mut a1, mut a0) = if l >= n {
-        ([].to_vec(), a[0..n].to_vec())
-    } else {
-        (a[l..n].to_vec(), a[0..l].to_vec())
-    };
+pub(crate) fn toom3_sqr<B: LimbBackend>(
+    a: &[IntLimb],
+    sqr_function: SqrFunction,
+    workspace: &mut Workspace,
+) -> Vec<IntLimb> {
+    let n = a.len();
+    if n == 0 {
+        return Vec::new();
+    }
 
-    let (mut b1, mut b0) = if l >= m {
-        ([].to_vec(), b[0..m].to_vec())
-    } else {
-        (b[l..m].to_vec(), b[0..l].to_vec())
-    };
+    let l = n.div_ceil(3);
 
-    trim_zeros(&mut a1);
-    trim_zeros(&mut b1);
-    trim_zeros(&mut a0);
-    trim_zeros(&mut b0);
+    let w: Vec<Signed<B>> = toom3_evaluate::<B>(a, l)
+        .iter()
+        .map(|x| x.sqr(sqr_function, workspace))
+        .collect();
 
-    let z0 = karatsuba(&a0, &b0, threshold, mul_function);
-    let z2 = karatsuba(&a1, &b1, threshold, mul_function);
+    toom3_interpolate::<B>(&w, l, 2 * n)
+}
 
-    unsafe {
-        let mut ab0 = vec![0; usize::max(a0.len(), a1.len()) + 1];
-        let mut ab1 = vec![0; usize::max(b0.len(), b1.len()) + 1];
+// Evaluation at 0, 1, -1, 2, -2, 1/2 and infinity, with the 1/2 point scaled by 8 so it
+// stays integral.
+fn toom4_evaluate<B: LimbBackend>(x: &[IntLimb], l: usize) -> Vec<Signed<B>> {
+    let p = split::<B>(x, 4, l);
 
-        wrapped_ops::unsafe_add_two_slices(&a0, &a1, &mut ab0);
-        wrapped_ops::unsafe_add_two_slices(&b0, &b1, &mut ab1);
+    let even1 = p[0].add(&p[2]);
+    let odd1 = p[1].add(&p[3]);
+    let even2 = p[0].add(&p[2].shl(2));
+    let odd2 = p[1].shl(1).add(&p[3].shl(3));
+    let half = p[0].shl(1).add(&p[1]).shl(1).add(&p[2]).shl(1).add(&p[3]);
 
-        trim_zeros(&mut ab0);
-        trim_zeros(&mut ab1);
+    vec![
+        p[0].clone(),
+        even1.add(&odd1),
+        even1.sub(&odd1),
+        even2.add(&odd2),
+        even2.sub(&odd2),
+        half,
+        p[3].clone(),
+    ]
+}
 
-        let mut z1 = karatsuba(&ab1, &ab0, threshold, mul_function);
+fn toom4_interpolate<B: LimbBackend>(w: &[Signed<B>], l: usize, len: usize) -> Vec<IntLimb> {
+    let (w0, w1, wm1, w2, wm2, whalf, winf) = (&w[0], &w[1], &w[2], &w[3], &w[4], &w[5], &w[6]);
 
-        if !z2.is_empty() {
-            sub_two_slices(
-                z1.as_ptr(),
-                z2.as_ptr(),
-                z1.as_mut_ptr(),
-                z1.len() as IntLimb,
-                z2.len() as IntLimb,
-            );
+    let even1 = w1.add(wm1).div_exact(2).sub(w0).sub(winf);
+    let odd1 = w1.sub(wm1).div_exact(2);
+    let even2 = w2
+        .add(wm2)
+        .div_exact(2)
+        .sub(w0)
+        .sub(&winf.shl(6))
+        .div_exact(4);
+    let odd2 = w2.sub(wm2).div_exact(4);
+
+    // even1 = c2 + c4, even2 = c2 + 4 c4
+    let c4 = even2.sub(&even1).div_exact(3);
+    let c2 = even1.sub(&c4);
+
+    // odd1 = c1 + c3 + c5, odd2 = c1 + 4 c3 + 16 c5, h = 16 c1 + 4 c3 + c5
+    let h = whalf
+        .sub(&w0.shl(6))
+        .sub(&c2.shl(4))
+        .sub(&c4.shl(2))
+        .sub(winf)
+        .div_exact(2);
+    let x = h.sub(&odd1).div_exact(3);
+    let y = odd2.sub(&odd1).div_exact(3);
+    let c3 = odd1.mul_limb(5).sub(&x).sub(&y).div_exact(3);
+    let c1 = x.sub(&c3).div_exact(5);
+    let c5 = y.sub(&c3).div_exact(5);
+
+    recompose::<B>(&[w0.clone(), c1, c2, c3, c4, c5, winf.clone()], l, len)
+}
+
+pub(crate) fn toom4<B: LimbBackend>(
+    a: &[IntLimb],
+    b: &[IntLimb],
+    mul_function: MulFunction,
+    workspace: &mut Workspace,
+) -> Vec<IntLimb> {
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 {
+        return Vec::new();
+    }
+
+    let l = usize::max(n, m).div_ceil(4);
+    if usize::min(n, m) <= 3 * l {
+        return mul_sliced::<B>(a, b, mul_function, workspace);
+    }
+
+    let (pa, pb) = (toom4_evaluate::<B>(a, l), toom4_evaluate::<B>(b, l));
+    let w: Vec<Signed<B>> = pa
+        .iter()
+        .zip(pb.iter())
+        .map(|(x, y)| x.mul(y, mul_function, workspace))
+        .collect();
+
+    toom4_interpolate::<B>(&w, l, n + m)
+}
+
+pub(crate) fn toom4_sqr<B: LimbBackend>(
+    a: &[IntLimb],
+    sqr_function: SqrFunction,
+    workspace: &mut Workspace,
+) -> Vec<IntLimb> {
+    let n = a.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let l = n.div_ceil(4);
+
+    let w: Vec<Signed<B>> = toom4_evaluate::<B>(a, l)
+        .iter()
+        .map(|x| x.sqr(sqr_function, workspace))
+        .collect();
+
+    toom4_interpolate::<B>(&w, l, 2 * n)
+}
+
+// Primes c * 2^k + 1 below 2^63 (k >= 55) and a primitive root of each. Their product
+// exceeds 2^186, enough to recover convolution coefficients of any practical length.
+const NTT_PRIMES: [(u64, u64); 3] = [
+    (4179340454199820289, 3),
+    (6269010681299730433, 5),
+    (4719772409484279809, 3),
+];
+
+// Arithmetic modulo an odd p < 2^63 with values kept in Montgomery form x * 2^64 mod p.
+struct Montgomery {
+    p: u64,
+    neg_p_inv: u64,
+    r2: u64,
+}
+
+impl Montgomery {
+    fn new(p: u64) -> Self {
+        // Newton iteration doubles the correct low bits of p^-1 mod 2^64 each step.
+        let mut inv: u64 = p;
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(p.wrapping_mul(inv)));
         }
 
-        trim_zeros(&mut z1);
+        let r = ((1u128 << 64) % p as u128) as u64;
+        let r2 = (r as u128 * r as u128 % p as u128) as u64;
 
-        if !z0.is_empty() {
-            sub_two_slices(
-                z1.as_ptr(),
-                z0.as_ptr(),
-                z1.as_mut_ptr(),
-                z1.len() as IntLimb,
-                z0.len() as IntLimb,
-            );
+        Montgomery {
+            p,
+            neg_p_inv: inv.wrapping_neg(),
+            r2,
         }
+    }
 
-        trim_zeros(&mut z1);
+    #[inline]
+    fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.neg_p_inv);
+        let u = ((t + m as u128 * self.p as u128) >> 64) as u64;
+        if u >= self.p {
+            u - self.p
+        } else {
+            u
+        }
+    }
 
-        add_helper(&mut dest, &z0, l as IntLimb, 0);
-        add_helper(&mut dest, &z1, l as IntLimb, l as isize);
-        add_helper(&mut dest, &z2, l as IntLimb, 2 * l as isize);
+    #[inline]
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
     }
 
+    #[inline]
+    fn add(&self, a: u64, b: u64) -> u64 {
+        let s = a + b;
+        if s >= self.p {
+            s - self.p
+        } else {
+            s
+        }
+    }
+
+    #[inline]
+    fn sub(&self, a: u64, b: u64) -> u64 {
+        if a >= b {
+            a - b
+        } else {
+            a + self.p - b
+        }
+    }
+
+    // Accepts any u64, not only reduced values.
+    fn encode(&self, a: u64) -> u64 {
+        self.mul(a, self.r2)
+    }
+
+    fn decode(&self, a: u64) -> u64 {
+        self.reduce(a as u128)
+    }
+
+    fn pow(&self, base: u64, mut e: u64) -> u64 {
+        let (mut base, mut res) = (base, self.encode(1));
+        while e > 0 {
+            if e & 1 == 1 {
+                res = self.mul(res, base);
+            }
+            base = self.mul(base, base);
+            e >>= 1;
+        }
+        res
+    }
+
+    // Powers of `w` for the butterflies of a transform of length `size`.
+    fn roots(&self, w: u64, size: usize) -> Vec<u64> {
+        let mut roots = Vec::with_capacity(size / 2);
+        let mut x = self.encode(1);
+        for _ in 0..size / 2 {
+            roots.push(x);
+            x = self.mul(x, w);
+        }
+        roots
+    }
+
+    // Decimation in frequency, natural order in and bit-reversed order out.
+    fn forward(&self, a: &mut [u64], roots: &[u64]) {
+        let n = a.len();
+        let mut len = n;
+        while len >= 2 {
+            let (half, step) = (len / 2, n / len);
+            for block in a.chunks_mut(len) {
+                let (lo, hi) = block.split_at_mut(half);
+                for j in 0..half {
+                    let (u, v) = (lo[j], hi[j]);
+                    lo[j] = self.add(u, v);
+                    hi[j] = self.mul(self.sub(u, v), roots[j * step]);
+                }
+            }
+            len /= 2;
+        }
+    }
+
+    // Decimation in time, bit-reversed order in and natural order out, without the 1/n.
+    fn inverse(&self, a: &mut [u64], roots: &[u64]) {
+        let n = a.len();
+        let mut len = 2;
+        while len <= n {
+            let (half, step) = (len / 2, n / len);
+            for block in a.chunks_mut(len) {
+                let (lo, hi) = block.split_at_mut(half);
+                for j in 0..half {
+                    let (u, v) = (lo[j], self.mul(hi[j], roots[j * step]));
+                    lo[j] = self.add(u, v);
+                    hi[j] = self.sub(u, v);
+                }
+            }
+            len *= 2;
+        }
+    }
+}
+
+// Cyclic convolution of `a` and `b` modulo `p`, with `size` a power of two.
+fn ntt_convolution(a: &[IntLimb], b: &[IntLimb], size: usize, p: u64, g: u64) -> Vec<u64> {
+    let mont = Montgomery::new(p);
+
+    let w = mont.pow(mont.encode(g), (p - 1) / size as u64);
+    let w_inv = mont.pow(w, size as u64 - 1);
+
+    let transform = |x: &[IntLimb]| {
+        let mut t: Vec<u64> = x.iter().map(|&l| mont.encode(l as u64)).collect();
+        t.resize(size, 0);
+        mont.forward(&mut t, &mont.roots(w, size));
+        t
+    };
+
+    let mut fa = transform(a);
+    if std::ptr::eq(a, b) {
+        for x in fa.iter_mut() {
+            *x = mont.mul(*x, *x);
+        }
+    } else {
+        let fb = transform(b);
+        for (x, y) in fa.iter_mut().zip(fb.iter()) {
+            *x = mont.mul(*x, *y);
+        }
+    }
+
+    mont.inverse(&mut fa, &mont.roots(w_inv, size));
+
+    let size_inv = mont.pow(mont.encode(size as u64), p - 2);
+    for x in fa.iter_mut() {
+        *x = mont.decode(mont.mul(*x, size_inv));
+    }
+
+    fa
+}
+
+fn add_wide(acc: &mut [u64; 4], x: [u64; 3]) {
+    let mut carry = false;
+    for (a, &v) in acc.iter_mut().zip(x.iter().chain(std::iter::once(&0))) {
+        let (s1, c1) = a.overflowing_add(v);
+        let (s2, c2) = s1.overflowing_add(carry as u64);
+        *a = s2;
+        carry = c1 || c2;
+    }
+}
+
+// Returns the low limb of `acc` and shifts it out, limbs are narrower than the u64 words
+// on 32-bit targets.
+fn shift_out_limb(acc: &mut [u64; 4]) -> IntLimb {
+    let limb = acc[0] as IntLimb;
+
+    if IntLimb::BITS == u64::BITS {
+        *acc = [acc[1], acc[2], acc[3], 0];
+    } else {
+        let bits = IntLimb::BITS;
+        for i in 0..3 {
+            acc[i] = (acc[i] >> bits) | (acc[i + 1] << (u64::BITS - bits));
+        }
+        acc[3] >>= bits;
+    }
+
+    limb
+}
+
+// Three-prime number theoretic transform, recombining the residues of each coefficient
+// with Garner's algorithm. Squaring when `a` and `b` are the same slice saves a transform.
+pub(crate) fn ntt_mul(a: &[IntLimb], b: &[IntLimb]) -> Vec<IntLimb> {
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 {
+        return Vec::new();
+    }
+
+    let size = (n + m - 1).next_power_of_two();
+
+    let residues: Vec<Vec<u64>> = NTT_PRIMES
+        .iter()
+        .map(|&(p, g)| ntt_convolution(a, b, size, p, g))
+        .collect();
+
+    let [(p1, _), (p2, _), (p3, _)] = NTT_PRIMES;
+    let (m2, m3) = (Montgomery::new(p2), Montgomery::new(p3));
+
+    // Multiplying a plain value by the Montgomery form of c gives the plain product.
+    let p1_inv_2 = m2.pow(m2.encode(p1), p2 - 2);
+    let p1_inv_3 = m3.pow(m3.encode(p1), p3 - 2);
+    let p2_inv_3 = m3.pow(m3.encode(p2), p3 - 2);
+    let p1p2 = p1 as u128 * p2 as u128;
+
+    let mut dest = Vec::with_capacity(n + m);
+    let mut acc = [0u64; 4];
+
+    let coefficients = residues[0]
+        .iter()
+        .zip(residues[1].iter())
+        .zip(residues[2].iter());
+    for ((&r1, &r2), &r3) in coefficients.take(n + m - 1) {
+        let v1 = r1;
+        let v2 = m2.mul(m2.sub(r2, v1 % p2), p1_inv_2);
+        let v3 = m3.mul(
+            m3.sub(m3.mul(m3.sub(r3, v1 % p3), p1_inv_3), v2 % p3),
+            p2_inv_3,
+        );
+
+        // x = v1 + v2 p1 + v3 p1 p2
+        let low = v1 as u128 + v2 as u128 * p1 as u128;
+        let t0 = v3 as u128 * (p1p2 as u64) as u128;
+        let t1 = v3 as u128 * (p1p2 >> 64);
+
+        add_wide(&mut acc, [low as u64, (low >> 64) as u64, 0]);
+        add_wide(&mut acc, [t0 as u64, (t0 >> 64) as u64, 0]);
+        add_wide(&mut acc, [0, t1 as u64, (t1 >> 64) as u64]);
+
+        dest.push(shift_out_limb(&mut acc));
+    }
+    dest.push(acc[0] as IntLimb);
+
     trim_zeros(&mut dest);
     dest
 }
 
+// Limbs `start..end` of `a`, i.e. (a / B^start) mod B^(end - start).
+fn limbs_range(a: &[IntLimb], start: usize, end: usize) -> Vec<IntLimb> {
+    let (start, end) = (usize::min(start, a.len()), usize::min(end, a.len()));
+    let mut repr = a[start..end].to_vec();
+    trim_zeros(&mut repr);
+    repr
+}
+
+// a * B^k
+fn shift_limbs(a: &[IntLimb], k: usize) -> Vec<IntLimb> {
+    if a.is_empty() {
+        return Vec::new();
+    }
+
+    let mut repr = vec![0; k + a.len()];
+    repr[k..].copy_from_slice(a);
+    repr
+}
+
+// Divides `a` by `b` where `b` has `n` limbs with the top bit set and `a < b * B^n`.
+fn div_2n_1n<B: LimbBackend>(
+    a: &[IntLimb],
+    b: &[IntLimb],
+    threshold: usize,
+    div_function: DivFunction,
+    workspace: &mut Workspace,
+) -> (Vec<IntLimb>, Vec<IntLimb>) {
+    let n = b.len();
+
+    if n <= threshold || a.len() <= n {
+        return div_function(a, b, workspace);
+    }
+
+    // Halves have to be equal, an extra low limb in both operands keeps the quotient.
+    if n % 2 == 1 {
+        let (q, r) = div_2n_1n::<B>(
+            &shift_limbs(a, 1),
+            &shift_limbs(b, 1),
+            threshold,
+            div_function,
+            workspace,
+        );
+        return (q, limbs_range(&r, 1, r.len()));
+    }
+
+    let half = n / 2;
+    let (b1, b2) = (limbs_range(b, half, n), limbs_range(b, 0, half));
+
+    let (q1, r) = div_3n_2n::<B>(
+        &limbs_range(a, n, a.len()),
+        &limbs_range(a, half, n),
+        b,
+        (&b1, &b2),
+        threshold,
+        div_function,
+        workspace,
+    );
+    let (q2, r) = div_3n_2n::<B>(
+        &r,
+        &limbs_range(a, 0, half),
+        b,
+        (&b1, &b2),
+        threshold,
+        div_function,
+        workspace,
+    );
+
+    (add::<B>(&shift_limbs(&q1, half), &q2), r)
+}
+
+// Divides `a12 * B^h + a3` by `b = b1 * B^h + b2`, where `b` has 2h limbs, `a3 < B^h`
+// and `a12 < b`.
+fn div_3n_2n<B: LimbBackend>(
+    a12: &[IntLimb],
+    a3: &[IntLimb],
+    b: &[IntLimb],
+    (b1, b2): (&[IntLimb], &[IntLimb]),
+    threshold: usize,
+    div_function: DivFunction,
+    workspace: &mut Workspace,
+) -> (Vec<IntLimb>, Vec<IntLimb>) {
+    let h = b.len() / 2;
+
+    // Estimate the quotient from the top limbs, it is at most 2 too large.
+    let (mut q, r) = if limbs_range(a12, h, a12.len()) == b1 {
+        (vec![IntLimb::MAX; h], add::<B>(&limbs_range(a12, 0, h), b1))
+    } else {
+        div_2n_1n::<B>(a12, b1, threshold, div_function, workspace)
+    };
+
+    let mut r = Signed::<B>::from_repr(add::<B>(&shift_limbs(&r, h), a3))
+        .sub(&Signed::from_repr(mul_with::<B>(&q, b2, workspace)));
+
+    while r.sign < 0 {
+        q = sub_limb::<B>(&q, 1).1;
+        r = r.add_signed(1, b);
+    }
+
+    (q, r.repr)
+}
+
+// Splits `a` into blocks of the divisor's length and divides them from the top, carrying
+// the remainder into the next block, so every step satisfies `r * B^n + block < b * B^n`.
+fn divide_blocks<B: LimbBackend>(
+    a: &[IntLimb],
+    b: &[IntLimb],
+    mut div_step: impl FnMut(&[IntLimb]) -> (Vec<IntLimb>, Vec<IntLimb>),
+) -> (Vec<IntLimb>, Vec<IntLimb>) {
+    let n = b.len();
+    let blocks = a.len().div_ceil(n);
+
+    let mut q = vec![0; blocks * n];
+    let mut r = Vec::new();
+
+    for i in (0..blocks).rev() {
+        let u = add::<B>(&shift_limbs(&r, n), &limbs_range(a, i * n, (i + 1) * n));
+        let (qi, ri) = div_step(&u);
+
+        q[i * n..i * n + qi.len()].copy_from_slice(&qi);
+        r = ri;
+    }
+
+    trim_zeros(&mut q);
+    (q, r)
+}
+
+// Burnikel and Ziegler, Fast Recursive Division, 1998.
+pub(crate) fn burnikel_ziegler<B: LimbBackend>(
+    a: &[IntLimb],
+    b: &[IntLimb],
+    threshold: usize,
+    div_function: DivFunction,
+    workspace: &mut Workspace,
+) -> (Vec<IntLimb>, Vec<IntLimb>) {
+    let shift = b[b.len() - 1].leading_zeros() as usize;
+    let (a, b) = (shl::<B>(a, shift), shl::<B>(b, shift));
+
+    let (q, r) = divide_blocks::<B>(&a, &b, |u| {
+        div_2n_1n::<B>(u, &b, threshold, div_function, workspace)
+    });
+
+    (q, shr::<B>(&r, shift).0)
+}
+
+// Approximates B^(2n) / v for `v` with n limbs and the top bit set to within a few
+// units, doubling the precision with a Newton step on every level.
+fn approximate_reciprocal<B: LimbBackend>(
+    v: &[IntLimb],
+    threshold: usize,
+    div_function: DivFunction,
+    workspace: &mut Workspace,
+) -> Vec<IntLimb> {
+    let n = v.len();
+
+    if n < threshold {
+        return div_function(&shift_limbs(&[1], 2 * n), v, workspace).0;
+    }
+
+    // x = xh * B^(n - h), only the nonzero limbs of x take part in the products.
+    let h = n / 2 + 1;
+    let xh = approximate_reciprocal::<B>(&v[n - h..], threshold, div_function, workspace);
+
+    // x + x (B^(2n) - v x) / B^(2n)
+    let e = Signed::<B>::from_repr(shift_limbs(&[1], n + h))
+        .sub(&Signed::from_repr(mul_with::<B>(v, &xh, workspace)));
+    let correction = limbs_range(&mul_with::<B>(&xh, &e.repr, workspace), 2 * h, usize::MAX);
+
+    Signed::<B>::from_repr(shift_limbs(&xh, n - h))
+        .add_signed(e.sign, &correction)
+        .repr
+}
+
+// floor(B^(2n) / v) for `v` with n limbs and the top bit set.
+fn reciprocal<B: LimbBackend>(
+    v: &[IntLimb],
+    threshold: usize,
+    div_function: DivFunction,
+    workspace: &mut Workspace,
+) -> Vec<IntLimb> {
+    let mut x = approximate_reciprocal::<B>(v, threshold, div_function, workspace);
+
+    let mut r = Signed::<B>::from_repr(shift_limbs(&[1], 2 * v.len()))
+        .sub(&Signed::from_repr(mul_with::<B>(v, &x, workspace)));
+    while r.sign < 0 {
+        x = sub_limb::<B>(&x, 1).1;
+        r = r.add_signed(1, v);
+    }
+    while r.sign > 0 && cmp_repr::<B>(&r.repr, v) != Ordering::Less {
+        x = add::<B>(&x, &[1]);
+        r = r.add_signed(-1, v);
+    }
+
+    x
+}
+
+// Division by multiplication with a precomputed reciprocal of the divisor.
+pub(crate) fn newton_div<B: LimbBackend>(
+    a: &[IntLimb],
+    b: &[IntLimb],
+    threshold: usize,
+    div_function: DivFunction,
+    workspace: &mut Workspace,
+) -> (Vec<IntLimb>, Vec<IntLimb>) {
+    let shift = b[b.len() - 1].leading_zeros() as usize;
+    let (a, b) = (shl::<B>(a, shift), shl::<B>(b, shift));
+    let n = b.len();
+
+    let inv = reciprocal::<B>(&b, threshold, div_function, workspace);
+
+    let (q, r) = divide_blocks::<B>(&a, &b, |u| {
+        // Only the top limbs of u matter, the estimate is never more than the real
+        // quotient, since inv <= B^(2n) / b, and at most a few units less.
+        let top = limbs_range(u, n - 1, usize::MAX);
+        let mut q = limbs_range(&mul_with::<B>(&top, &inv, workspace), n + 1, usize::MAX);
+        let (_, mut r) = sub::<B>(u, &mul_with::<B>(&q, &b, workspace));
+
+        while cmp_repr::<B>(&r, &b) != Ordering::Less {
+            q = add::<B>(&q, &[1]);
+            r = sub::<B>(&r, &b).1;
+        }
+
+        (q, r)
+    });
+
+    (q, shr::<B>(&r, shift).0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::base_ops::base_mul;
+    use crate::backend::Native;
+    use crate::base_ops::{base_mul, mul};
+
+    fn karatsuba_vec(a: &[IntLimb], b: &[IntLimb], threshold: usize) -> Vec<IntLimb> {
+        let mut dest = vec![IntLimb::MAX; a.len() + b.len()];
+        // Exactly the bound, so an undersized one panics on the slicing.
+        let mut scratch = vec![0; karatsuba_scratch_size(a.len(), b.len(), threshold)];
+
+        karatsuba::<Native>(a, b, &mut dest, threshold, &mut scratch);
+        trim_zeros(&mut dest);
+        dest
+    }
+
+    fn karatsuba_sqr_vec(a: &[IntLimb], threshold: usize) -> Vec<IntLimb> {
+        let mut dest = vec![IntLimb::MAX; 2 * a.len()];
+        let mut scratch = vec![0; karatsuba_sqr_scratch_size(a.len(), threshold)];
+
+        karatsuba_sqr::<Native>(a, &mut dest, threshold, &mut scratch);
+        trim_zeros(&mut dest);
+        dest
+    }
+
+    fn base_sqr_with(a: &[IntLimb], _: &mut Workspace) -> Vec<IntLimb> {
+        crate::base_ops::base_sqr::<Native>(a)
+    }
 
     #[test]
     fn karatsuba_test_1() {
@@ -123,7 +985,7 @@ mod tests {
         let b = Vec::from([1, 1, 1, 1, 1, 1]);
 
         let expected_res = Vec::from([1, 2, 3, 4, 5, 6, 5, 4, 3, 2, 1]);
-        let res = karatsuba(&a, &b, 3, base_mul);
+        let res = karatsuba_vec(&a, &b, 3);
 
         assert_eq!(expected_res, res);
     }
@@ -137,7 +999,346 @@ mod tests {
             36, 84, 57, 94, 122, 80, 108, 53, 76, 108, 110, 113, 142, 46, 71, 41, 6, 21,
         ]);
 
-        let res = karatsuba(&a, &b, 7, base_mul);
+        let res = karatsuba_vec(&a, &b, 7);
         assert_eq!(expected_res, res);
     }
+
+    fn sample_limbs(len: usize, seed: u64) -> Vec<IntLimb> {
+        let mut state = seed | 1;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as IntLimb
+            })
+            .collect()
+    }
+
+    #[test]
+    fn karatsuba_fits_scratch_bound() {
+        let x = IntLimb::MAX;
+
+        for &threshold in [3, 4, 13].iter() {
+            for &(n, m) in [
+                (4, 4),
+                (5, 4),
+                (9, 4),
+                (33, 17),
+                (64, 1),
+                (100, 99),
+                (149, 60),
+            ]
+            .iter()
+            {
+                let a = sample_limbs(n, (n + threshold) as u64);
+                let b = sample_limbs(m, (m * threshold) as u64);
+
+                assert_eq!(base_mul::<Native>(&a, &b), karatsuba_vec(&a, &b, threshold));
+                assert_eq!(
+                    base_mul::<Native>(&vec![x; n], &vec![x; m]),
+                    karatsuba_vec(&vec![x; n], &vec![x; m], threshold),
+                    "{} x {} all ones",
+                    n,
+                    m
+                );
+            }
+        }
+    }
+
+    fn base_mul_with(a: &[IntLimb], b: &[IntLimb], _: &mut Workspace) -> Vec<IntLimb> {
+        base_mul::<Native>(a, b)
+    }
+
+    type Toom = fn(&[IntLimb], &[IntLimb], MulFunction, &mut Workspace) -> Vec<IntLimb>;
+
+    fn check_against_base_mul(toom: Toom) {
+        let sizes = [
+            (1, 1),
+            (3, 3),
+            (4, 4),
+            (7, 5),
+            (12, 12),
+            (40, 41),
+            (100, 9),
+            (9, 100),
+            (257, 250),
+            (250, 90),
+        ];
+
+        for (i, &(n, m)) in sizes.iter().enumerate() {
+            let a = sample_limbs(n, 2 * i as u64 + 1);
+            let b = sample_limbs(m, 2 * i as u64 + 2);
+
+            assert_eq!(
+                base_mul::<Native>(&a, &b),
+                toom(&a, &b, base_mul_with, &mut Workspace::new()),
+                "{} x {}",
+                n,
+                m
+            );
+            assert_eq!(
+                base_mul::<Native>(&a, &b),
+                toom(&a, &b, mul_with::<Native>, &mut Workspace::new()),
+                "{} x {}",
+                n,
+                m
+            );
+        }
+    }
+
+    #[test]
+    fn toom3_matches_base_mul() {
+        check_against_base_mul(toom3::<Native>);
+    }
+
+    #[test]
+    fn toom4_matches_base_mul() {
+        check_against_base_mul(toom4::<Native>);
+    }
+
+    #[test]
+    fn toom_all_ones() {
+        // Maximal limbs give the largest evaluations and carries.
+        let a = vec![IntLimb::MAX; 61];
+        let b = vec![IntLimb::MAX; 47];
+
+        assert_eq!(
+            base_mul::<Native>(&a, &b),
+            toom3::<Native>(&a, &b, base_mul_with, &mut Workspace::new())
+        );
+        assert_eq!(
+            base_mul::<Native>(&a, &b),
+            toom4::<Native>(&a, &b, base_mul_with, &mut Workspace::new())
+        );
+    }
+
+    #[test]
+    fn toom_with_zero_pieces() {
+        let mut a = vec![0; 30];
+        a[29] = 5;
+        let b = Vec::from([0, 0, 0, 0, 0, 0, 0, 0, 7]);
+
+        assert_eq!(
+            base_mul::<Native>(&a, &b),
+            toom3::<Native>(&a, &b, base_mul_with, &mut Workspace::new())
+        );
+        assert_eq!(
+            base_mul::<Native>(&a, &b),
+            toom4::<Native>(&a, &b, base_mul_with, &mut Workspace::new())
+        );
+        assert_eq!(
+            Vec::<IntLimb>::new(),
+            toom4::<Native>(&[], &b, base_mul_with, &mut Workspace::new())
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn mul_ladder_matches_base_mul() {
+        for &(n, m) in [(130, 140), (450, 420), (1200, 1100), (2000, 500)].iter() {
+            let a = sample_limbs(n, n as u64);
+            let b = sample_limbs(m, m as u64 + 7);
+
+            assert_eq!(
+                base_mul::<Native>(&a, &b),
+                mul::<Native>(&a, &b),
+                "{} x {}",
+                n,
+                m
+            );
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn ntt_matches_karatsuba() {
+        for &(n, m) in [
+            (1, 1),
+            (2, 1),
+            (5, 3),
+            (64, 64),
+            (100, 37),
+            (513, 1000),
+            (3000, 2500),
+        ]
+        .iter()
+        {
+            let a = sample_limbs(n, 3 * n as u64);
+            let b = sample_limbs(m, 5 * m as u64);
+
+            assert_eq!(karatsuba_vec(&a, &b, 13), ntt_mul(&a, &b), "{} x {}", n, m);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn ntt_all_ones() {
+        // Every coefficient is close to the CRT bound per length.
+        let a = vec![IntLimb::MAX; 777];
+        let b = vec![IntLimb::MAX; 1024];
+
+        assert_eq!(karatsuba_vec(&a, &b, 13), ntt_mul(&a, &b));
+        assert_eq!(Vec::<IntLimb>::new(), ntt_mul(&a, &[]));
+    }
+
+    #[test]
+    fn montgomery_arithmetic() {
+        for &(p, _) in NTT_PRIMES.iter() {
+            let mont = Montgomery::new(p);
+            let (a, b) = (u64::MAX, p - 1);
+
+            let product = mont.decode(mont.mul(mont.encode(a), mont.encode(b)));
+            assert_eq!((a as u128 * b as u128 % p as u128) as u64, product);
+            assert_eq!(mont.encode(1), mont.pow(mont.encode(3), p - 1));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn squaring_matches_base_mul() {
+        use crate::base_ops::{base_sqr, sqr_with};
+
+        for &n in [1, 2, 5, 21, 64, 100, 333].iter() {
+            let a = sample_limbs(n, 11 * n as u64);
+            let expected = base_mul::<Native>(&a, &a.clone());
+
+            assert_eq!(expected, base_sqr::<Native>(&a), "{}", n);
+            assert_eq!(expected, karatsuba_sqr_vec(&a, 4), "{}", n);
+            assert_eq!(
+                expected,
+                toom3_sqr::<Native>(&a, sqr_with::<Native>, &mut Workspace::new()),
+                "{}",
+                n
+            );
+            assert_eq!(
+                expected,
+                toom4_sqr::<Native>(&a, sqr_with::<Native>, &mut Workspace::new()),
+                "{}",
+                n
+            );
+            assert_eq!(expected, ntt_mul(&a, &a), "{}", n);
+        }
+    }
+
+    #[test]
+    fn squaring_all_ones() {
+        let a = vec![IntLimb::MAX; 101];
+        let expected = base_mul::<Native>(&a, &a.clone());
+        let mut workspace = Workspace::new();
+
+        assert_eq!(expected, karatsuba_sqr_vec(&a, 4));
+        assert_eq!(
+            expected,
+            toom3_sqr::<Native>(&a, base_sqr_with, &mut workspace)
+        );
+        assert_eq!(
+            expected,
+            toom4_sqr::<Native>(&a, base_sqr_with, &mut workspace)
+        );
+        assert_eq!(
+            Vec::<IntLimb>::new(),
+            toom4_sqr::<Native>(&[], base_sqr_with, &mut workspace)
+        );
+    }
+
+    fn check_division(a: &[IntLimb], b: &[IntLimb], (q, r): (Vec<IntLimb>, Vec<IntLimb>)) {
+        assert_eq!(
+            Ordering::Less,
+            cmp_repr::<Native>(&r, b),
+            "{} / {}",
+            a.len(),
+            b.len()
+        );
+        assert_eq!(
+            a.to_vec(),
+            add::<Native>(&mul::<Native>(&q, b), &r),
+            "{} / {}",
+            a.len(),
+            b.len()
+        );
+    }
+
+    #[test]
+    fn burnikel_ziegler_divides() {
+        use crate::base_ops::div;
+        let div_function: DivFunction = |a, b, _| div::<Native>(a, b).unwrap();
+
+        for &(m, n) in [
+            (10, 9),
+            (100, 20),
+            (150, 73),
+            (400, 100),
+            (401, 200),
+            (1000, 999),
+        ]
+        .iter()
+        {
+            let a = sample_limbs(m, m as u64);
+            let mut b = sample_limbs(n, n as u64 + 1);
+            b[n - 1] >>= n % 7;
+
+            check_division(
+                &a,
+                &b,
+                burnikel_ziegler::<Native>(&a, &b, 8, div_function, &mut Workspace::new()),
+            );
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn newton_divides() {
+        use crate::base_ops::div;
+        let div_function: DivFunction = |a, b, _| div::<Native>(a, b).unwrap();
+
+        for &(m, n) in [
+            (10, 9),
+            (100, 20),
+            (150, 73),
+            (400, 100),
+            (401, 200),
+            (1000, 999),
+        ]
+        .iter()
+        {
+            let a = sample_limbs(m, 3 * m as u64);
+            let mut b = sample_limbs(n, 3 * n as u64 + 1);
+            b[n - 1] >>= n % 5;
+
+            check_division(
+                &a,
+                &b,
+                newton_div::<Native>(&a, &b, 8, div_function, &mut Workspace::new()),
+            );
+        }
+    }
+
+    #[test]
+    fn division_with_extreme_quotient_digits() {
+        use crate::base_ops::div;
+        let div_function: DivFunction = |a, b, _| div::<Native>(a, b).unwrap();
+
+        // b * (B^k - 1) + (b - 1) drives every estimate to its upper bound.
+        let b = vec![IntLimb::MAX; 37];
+        let q = vec![IntLimb::MAX; 80];
+        let b_minus_one = sub_limb::<Native>(&b, 1).1;
+        let a = add::<Native>(&mul::<Native>(&q, &b), &b_minus_one);
+
+        let mut single_high = vec![0; 41];
+        single_high[40] = 1 << (IntLimb::BITS - 1);
+
+        for b in [b, single_high].iter() {
+            check_division(
+                &a,
+                b,
+                burnikel_ziegler::<Native>(&a, b, 4, div_function, &mut Workspace::new()),
+            );
+            check_division(
+                &a,
+                b,
+                newton_div::<Native>(&a, b, 4, div_function, &mut Workspace::new()),
+            );
+        }
+    }
 }
@@ -0,0 +1,44 @@
+.text
+
+.globl      add_two_slices
+
+add_two_slices:
+    // x0, x1 - adresses of slices
+    // x2     - dst of the addition (at least len of bigger slice + 1)
+    // x3     - len of the larger slice
+    // x4     - len of the smaller slice
+
+    // limbs only in the larger slice
+    sub x3, x3, x4
+
+    // the carry stays in the flags, cbz and sub do not touch them
+    cmn xzr, xzr
+
+    .for_begin: // for x4 = n2; x4 > 0; x4--
+        cbz x4, .for_end
+
+        ldr x5, [x0], #8
+        ldr x6, [x1], #8
+        adcs x5, x5, x6
+        str x5, [x2], #8
+
+        sub x4, x4, #1
+        b .for_begin
+    .for_end:
+
+    // loop only through bigger slice
+    .for_one_slice_begin: // for x3 = n1 - n2; x3 > 0; x3--
+        cbz x3, .for_one_slice_end
+
+        ldr x5, [x0], #8
+        adcs x5, x5, xzr
+        str x5, [x2], #8
+
+        sub x3, x3, #1
+        b .for_one_slice_begin
+    .for_one_slice_end:
+
+    adc x5, xzr, xzr
+    str x5, [x2]
+
+    ret
@@ -0,0 +1,19 @@
+.text
+
+.globl      add_const
+
+add_const:
+    // Params:
+    // x0 - dst of the addition
+    // x1 - const to add, the carry is propagated until it stops
+
+    .loop_begin:
+        ldr x2, [x0]
+        adds x2, x2, x1
+        str x2, [x0], #8
+
+        // mov keeps the flags
+        mov x1, #1
+        b.cs .loop_begin
+
+    ret
@@ -0,0 +1,28 @@
+.text
+
+.globl      cmp_slices
+
+cmp_slices:
+    // Params:
+    // x0, x1 - adresses of slices
+    // x2     - len of slices
+    // Return:
+    // w0     - one of 1 - x0 is greater, -1 - x1 is greater, 0 - both are same.
+
+    .begin_loop: // for x2 = n; x2 > 0; x2--
+        cbz x2, .equal
+
+        sub x2, x2, #1
+        ldr x3, [x0, x2, lsl #3]
+        ldr x4, [x1, x2, lsl #3]
+
+        cmp x3, x4
+        b.eq .begin_loop
+
+    mov w0, #1
+    cneg w0, w0, lo
+    ret
+
+    .equal:
+    mov w0, #0
+    ret
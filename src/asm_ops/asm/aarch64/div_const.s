@@ -0,0 +1,99 @@
+.text
+
+.globl      div_const
+
+div_const:
+    // Params:
+    // x0 - dst of the division, most significant limb first
+    // x1 - const that x0 will be divided by
+    // x2 - len of x0
+    // Returns:
+    // x0 - remainder of the division
+
+    // there is no 128 by 64 bit division, so every limb is divided in two
+    // 32 bit halves by the normalized divisor (Hacker's Delight, divlu)
+    clz x3, x1
+    lsl x1, x1, x3
+    eor x4, x3, #63
+    lsr x5, x1, #32         // high half of the divisor
+    and x6, x1, #0xffffffff // low half of the divisor
+
+    // x7 - remainder, shifted left like the divisor
+    mov x7, #0
+    cbz x2, .end
+
+    // bits shifted out of the first limb, (x >> 1) >> (63 - s) = x >> (64 - s)
+    ldr x8, [x0]
+    lsr x7, x8, #1
+    lsr x7, x7, x4
+
+    .loop_begin: // for x2 = n; x2 > 0; x2--
+        sub x2, x2, #1
+
+        // the next limb, zero after the last one
+        mov x9, #0
+        cbz x2, .last_limb
+            ldr x9, [x0, #8]
+        .last_limb:
+
+        // x10 = limb shifted left like the divisor
+        lsl x10, x8, x3
+        lsr x11, x9, #1
+        lsr x11, x11, x4
+        orr x10, x10, x11
+
+        lsr x11, x10, #32           // high half of the limb
+        and x12, x10, #0xffffffff   // low half of the limb
+
+        // high half of the quotient, x7 < x1 so it fits after the adjustment
+        udiv x13, x7, x5
+        msub x14, x13, x5, x7
+        .adjust_high:
+            lsr x15, x13, #32
+            cbnz x15, .dec_high
+            mul x15, x13, x6
+            orr x16, x11, x14, lsl #32
+            cmp x15, x16
+            b.ls .adjust_high_end
+            .dec_high:
+            sub x13, x13, #1
+            add x14, x14, x5
+            lsr x15, x14, #32
+            cbz x15, .adjust_high
+        .adjust_high_end:
+
+        // remainder of the high half, below the divisor
+        orr x15, x11, x7, lsl #32
+        mul x16, x13, x1
+        sub x15, x15, x16
+
+        // low half of the quotient
+        udiv x17, x15, x5
+        msub x14, x17, x5, x15
+        .adjust_low:
+            lsr x16, x17, #32
+            cbnz x16, .dec_low
+            mul x16, x17, x6
+            orr x7, x12, x14, lsl #32
+            cmp x16, x7
+            b.ls .adjust_low_end
+            .dec_low:
+            sub x17, x17, #1
+            add x14, x14, x5
+            lsr x16, x14, #32
+            cbz x16, .adjust_low
+        .adjust_low_end:
+
+        orr x7, x12, x15, lsl #32
+        mul x16, x17, x1
+        sub x7, x7, x16
+
+        orr x17, x17, x13, lsl #32
+        str x17, [x0], #8
+
+        mov x8, x9
+        cbnz x2, .loop_begin
+
+    .end:
+    lsr x0, x7, x3
+    ret
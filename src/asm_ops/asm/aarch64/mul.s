@@ -0,0 +1,46 @@
+.text
+
+.globl      mul_two_slices
+
+mul_two_slices:
+    // x0, x1 - adresses of slices
+    // x2     - dst of the multiplication (at least len of x3 + x4, zeroed)
+    // x3     - len of the larger slice
+    // x4     - len of the smaller slice
+
+    stp x29, x30, [sp, #-64]!
+    mov x29, sp
+    stp x19, x20, [sp, #16]
+    stp x21, x22, [sp, #32]
+    str x23, [sp, #48]
+
+    mov x19, x0
+    mov x20, x1
+    mov x21, x2
+    mov x22, x3
+    mov x23, x4
+
+    // dst[i..i + x3] += a * b[i], one row per limb of the smaller slice
+    .loop_begin: // for x23 = n2; x23 > 0; x23--
+        cbz x23, .loop_end
+
+        mov x0, x19
+        mov x1, x21
+        mov x2, x22
+        ldr x3, [x20], #8
+        bl addmul_1
+
+        // dst[i + x3] is not written by the previous rows
+        str x0, [x21, x22, lsl #3]
+
+        add x21, x21, #8
+        sub x23, x23, #1
+        b .loop_begin
+    .loop_end:
+
+    ldr x23, [sp, #48]
+    ldp x21, x22, [sp, #32]
+    ldp x19, x20, [sp, #16]
+    ldp x29, x30, [sp], #64
+
+    ret
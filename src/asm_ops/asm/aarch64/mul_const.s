@@ -0,0 +1,31 @@
+.text
+
+.globl      mul_const
+
+mul_const:
+    // Params:
+    // x0 - dst of the multiplication, dst[n] is written only if the carry is not zero
+    // x1 - const to multiply x0 by
+    // x2 - len of x0
+
+    mov x3, #0
+    .loop_begin: // for x2 = n; x2 > 0; x2--
+        cbz x2, .loop_end
+
+        ldr x4, [x0]
+        mul x5, x4, x1
+        umulh x6, x4, x1    // x6:x5 = dst[i] * c
+
+        adds x5, x5, x3
+        adc x3, x6, xzr
+        str x5, [x0], #8
+
+        sub x2, x2, #1
+        b .loop_begin
+    .loop_end:
+
+    cbz x3, .skip_carry
+        str x3, [x0]
+    .skip_carry:
+
+    ret
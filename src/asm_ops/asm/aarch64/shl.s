@@ -0,0 +1,50 @@
+.text
+
+.globl      shl_slice
+
+shl_slice:
+    // Params:
+    // x0 - address of the slice
+    // x1 - dst of the shift (at least len of x0, can be the same as x0)
+    // x2 - len of x0
+    // x3 - shift, 0 <= x3 < 64
+    // Returns:
+    // x0 - bits shifted out of the most significant limb
+
+    mov x4, #0
+    cbz x2, .end
+
+    // x >> (64 - shift) as (x >> 1) >> (63 - shift), so a zero shift gives 0
+    eor x5, x3, #63
+
+    // go from the most significant limb so x1 = x0 is not overwritten before read
+    sub x2, x2, #1
+    ldr x6, [x0, x2, lsl #3]
+    lsr x4, x6, #1
+    lsr x4, x4, x5
+
+    .loop_begin: // for x2 = n - 1; x2 > 0; x2--
+        cbz x2, .loop_end
+
+        sub x2, x2, #1
+        ldr x7, [x0, x2, lsl #3]
+
+        // x6 = x6 << shift | high bits of x7
+        lsl x6, x6, x3
+        lsr x8, x7, #1
+        lsr x8, x8, x5
+        orr x6, x6, x8
+
+        add x8, x2, #1
+        str x6, [x1, x8, lsl #3]
+        mov x6, x7
+
+        b .loop_begin
+    .loop_end:
+
+    lsl x6, x6, x3
+    str x6, [x1]
+
+    .end:
+    mov x0, x4
+    ret
@@ -0,0 +1,48 @@
+.text
+
+.globl      shr_slice
+
+shr_slice:
+    // Params:
+    // x0 - address of the slice
+    // x1 - dst of the shift (at least len of x0, can be the same as x0)
+    // x2 - len of x0
+    // x3 - shift, 0 <= x3 < 64
+    // Returns:
+    // x0 - bits shifted out of the least significant limb, in the high bits
+
+    mov x4, #0
+    cbz x2, .end
+
+    // x << (64 - shift) as (x << 1) << (63 - shift), so a zero shift gives 0
+    eor x5, x3, #63
+
+    ldr x6, [x0], #8
+    lsl x4, x6, #1
+    lsl x4, x4, x5
+    sub x2, x2, #1
+
+    .loop_begin: // for x2 = n - 1; x2 > 0; x2--
+        cbz x2, .loop_end
+
+        ldr x7, [x0], #8
+
+        // x6 = x6 >> shift | low bits of x7
+        lsr x6, x6, x3
+        lsl x8, x7, #1
+        lsl x8, x8, x5
+        orr x6, x6, x8
+
+        str x6, [x1], #8
+        mov x6, x7
+
+        sub x2, x2, #1
+        b .loop_begin
+    .loop_end:
+
+    lsr x6, x6, x3
+    str x6, [x1]
+
+    .end:
+    mov x0, x4
+    ret
@@ -0,0 +1,113 @@
+.text
+
+.globl      sqr_slice
+
+sqr_slice:
+    // Params:
+    // x0 - address of the slice
+    // x1 - dst of the square (at least 2 * len of x0, must not overlap x0)
+    // x2 - len of x0
+
+    lsl x3, x2, #1
+
+    // dest = 0
+    mov x4, #0
+    .zero_loop_begin: // for x4 = 0; x4 < 2 * n; x4++
+        cmp x4, x3
+        b.hs .zero_loop_end
+
+        str xzr, [x1, x4, lsl #3]
+
+        add x4, x4, #1
+        b .zero_loop_begin
+    .zero_loop_end:
+
+    // sum of a[i] * a[j] for i < j, every product computed once
+    mov x4, #0
+    .outer_loop_begin: // for x4 = 0; x4 < n; x4++
+        cmp x4, x2
+        b.hs .outer_loop_end
+
+        ldr x5, [x0, x4, lsl #3]
+        mov x6, #0
+
+        add x7, x4, #1
+        .inner_loop_begin: // for x7 = x4 + 1; x7 < n; x7++
+            cmp x7, x2
+            b.hs .inner_loop_end
+
+            ldr x8, [x0, x7, lsl #3]
+            mul x9, x8, x5
+            umulh x10, x8, x5   // x10:x9 = a[i] * a[j]
+
+            // add carry and dest[i + j], can not overflow x10
+            adds x9, x9, x6
+            adc x10, x10, xzr
+
+            add x11, x4, x7
+            ldr x12, [x1, x11, lsl #3]
+            adds x9, x9, x12
+            adc x6, x10, xzr
+
+            str x9, [x1, x11, lsl #3]
+
+            add x7, x7, #1
+            b .inner_loop_begin
+        .inner_loop_end:
+
+        // dest[i + n] was not written by the previous rows
+        add x11, x4, x2
+        str x6, [x1, x11, lsl #3]
+
+        add x4, x4, #1
+        b .outer_loop_begin
+    .outer_loop_end:
+
+    // dest = 2 * dest
+    mov x4, #0
+    mov x6, #0
+    .double_loop_begin: // for x4 = 0; x4 < 2 * n; x4++
+        cmp x4, x3
+        b.hs .double_loop_end
+
+        ldr x8, [x1, x4, lsl #3]
+        lsr x9, x8, #63
+        orr x8, x6, x8, lsl #1
+        str x8, [x1, x4, lsl #3]
+        mov x6, x9
+
+        add x4, x4, #1
+        b .double_loop_begin
+    .double_loop_end:
+
+    // add a[i] * a[i] to dest[2i], dest[2i + 1]
+    mov x4, #0
+    mov x6, #0
+    .diagonal_loop_begin: // for x4 = 0; x4 < n; x4++
+        cmp x4, x2
+        b.hs .diagonal_loop_end
+
+        ldr x8, [x0, x4, lsl #3]
+        mul x9, x8, x8
+        umulh x10, x8, x8   // x10:x9 = a[i] * a[i]
+
+        adds x9, x9, x6
+        adc x10, x10, xzr
+
+        add x11, x4, x4
+        ldr x12, [x1, x11, lsl #3]
+        adds x9, x9, x12
+        adc x10, x10, xzr
+        str x9, [x1, x11, lsl #3]
+
+        add x11, x11, #1
+        ldr x12, [x1, x11, lsl #3]
+        adds x12, x12, x10
+        adc x6, xzr, xzr
+        str x12, [x1, x11, lsl #3]
+
+        add x4, x4, #1
+        b .diagonal_loop_begin
+    .diagonal_loop_end:
+
+    ret
@@ -0,0 +1,19 @@
+.text
+
+.globl      sub_const
+
+sub_const:
+    // Params:
+    // x0 - dst of the subtraction
+    // x1 - const to subtract, the borrow is propagated until it stops
+
+    .loop_begin:
+        ldr x2, [x0]
+        subs x2, x2, x1
+        str x2, [x0], #8
+
+        // mov keeps the flags, carry clear means a borrow
+        mov x1, #1
+        b.cc .loop_begin
+
+    ret
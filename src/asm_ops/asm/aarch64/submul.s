@@ -0,0 +1,38 @@
+.text
+
+.globl      submul_1
+
+submul_1:
+    // Params:
+    // x0 - address of the slice
+    // x1 - dst of the subtraction (at least len of x0, can not partially overlap x0)
+    // x2 - len of x0
+    // x3 - const to multiply x0 by
+    // Returns:
+    // x0 - borrow out of the most significant limb of x1
+
+    mov x4, #0
+    .loop_begin: // for x2 = n; x2 > 0; x2--
+        cbz x2, .loop_end
+
+        ldr x5, [x0], #8
+        ldr x6, [x1]
+        mul x7, x5, x3
+        umulh x8, x5, x3    // x8:x7 = a[i] * c
+
+        // add borrow, can not overflow x8
+        adds x7, x7, x4
+        adc x8, x8, xzr
+
+        // carry clear means dst[i] borrowed
+        subs x6, x6, x7
+        cinc x4, x8, cc
+
+        str x6, [x1], #8
+
+        sub x2, x2, #1
+        b .loop_begin
+    .loop_end:
+
+    mov x0, x4
+    ret
@@ -52,13 +52,14 @@ add_two_slices:
         cmp rcx, r10
         jle .for_one_slice_end
 
-            ## add from first slice
-            xor r12, r12
+            ## add carry to the value from first slice
             mov r11, [rdi]
             add r11, r12
             jnc .if_end_3
-                mov r12, 1
+                inc r13
             .if_end_3:
+            mov r12, r13
+            xor r13, r13
             mov [rdx], r11
 
         lea rdx, [rdx + 8]
@@ -0,0 +1,54 @@
+.intel_syntax noprefix
+.text
+
+.globl      add_two_slices_adx
+
+add_two_slices_adx:
+    ## rdi, rsi - adresses of slices
+    ## rdx      - dst of the addition (at least len of bigger slice + 1)
+    ## rcx      - len of the larger slice
+    ## r8       - len of the smaller slice
+    ## Requires ADX. The carry stays in CF, the loops only use jrcxz and lea.
+
+    ## limbs only in the larger slice
+    sub rcx, r8
+    mov r9, rcx
+    mov rcx, r8
+
+    xor rax, rax ## also clears CF
+
+    .for_begin: ## for rcx = r8; rcx > 0; rcx--
+        jrcxz .for_end
+
+        mov r10, [rdi]
+        adcx r10, [rsi]
+        mov [rdx], r10
+
+        lea rdi, [rdi + 8]
+        lea rsi, [rsi + 8]
+        lea rdx, [rdx + 8]
+        lea rcx, [rcx - 1]
+        jmp .for_begin
+    .for_end:
+
+    mov rcx, r9
+
+    ## loop only through bigger slice
+    .for_one_slice_begin: ## for rcx = r9; rcx > 0; rcx--
+        jrcxz .for_one_slice_end
+
+        mov r10, [rdi]
+        adcx r10, rax
+        mov [rdx], r10
+
+        lea rdi, [rdi + 8]
+        lea rdx, [rdx + 8]
+        lea rcx, [rcx - 1]
+        jmp .for_one_slice_begin
+    .for_one_slice_end:
+
+    ## rax = CF
+    adcx rax, rax
+    mov [rdx], rax
+
+    ret
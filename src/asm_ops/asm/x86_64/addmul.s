@@ -0,0 +1,38 @@
+.intel_syntax noprefix
+.text
+
+.globl      addmul_1
+
+addmul_1:
+    ## Params:
+    ## rdi - address of the slice
+    ## rsi - dst of the addition (at least len of rdi, can not partially overlap rdi)
+    ## rdx - len of rdi
+    ## rcx - const to multiply rdi by
+    ## Returns:
+    ## rax - carry out of the most significant limb of rsi
+
+    mov r8, rdx
+    xor r9, r9
+    xor r10, r10
+    .loop_begin: ## for r9 = 0; r9 < r8; r9++
+        cmp r8, r9
+        jle .loop_end
+
+        mov rax, [rdi + 8*r9]
+        mul rcx             ## rdx:rax = a[i] * c
+
+        ## add carry and dst[i], can not overflow rdx
+        add rax, r10
+        adc rdx, 0
+        add [rsi + 8*r9], rax
+        adc rdx, 0
+
+        mov r10, rdx
+
+        inc r9
+        jmp .loop_begin
+    .loop_end:
+
+    mov rax, r10
+    ret
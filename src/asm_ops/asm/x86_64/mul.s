@@ -5,7 +5,7 @@
 
 mul_two_slices:
     ## rdi, rsi - adresses of slices
-    ## rdx      - dst of the addition (at least len of r8 + r9)
+    ## rdx      - dst of the multiplication (at least len of rcx + r8, zeroed)
     ## rcx      - len of the larger slice
     ## r8       - len of the smaller slice
 
@@ -13,64 +13,35 @@ mul_two_slices:
     push r13
     push r14
     push r15
-
-    ## store address of dest in r15 
-    lea r15, [rdx]
-
-    xor r10, r10
-    xor r13, r13
-
-    .outer_loop_begin: ## for r10 = 0; r10 < rcx; r10++
-        cmp rcx, r10
-        jle .outer_loop_end
-
-        mov r13, r10
-        xor r11, r11
-        xor r12, r12
-        .inner_loop_begin: ## for r11 = 0; r11 < r8; r11++
-            cmp r8, r11
-            jle .inner_loop_end
-
-            mov rax, [rdi + 8*r10]
-            mov r14, [rsi + 8*r11]
-
-            mul r14             ## rax = a[i] * b[j]
-
-            add rax, r12        ## add carry
-            jnc .if_end_1
-                inc rdx
-            .if_end_1:
-
-            ## add to dest[i + j] value in rax
-            add [r15 + 8*r13], rax
-            jnc .end_adjust
-                inc rdx
-            .end_adjust:
-
-            ## set carry to value from rax
-            mov r12, rdx
-
-            inc r11
-            inc r13
-            jmp .inner_loop_begin
-        .inner_loop_end:
-
-        ## forward carry if needed
-        xor rax, rax
-        .begin_while:
-            cmp r12, rax
-            je .end_while
-            add [r15 + 8*r13], r12
-            jnc .end_while
-                mov r12, 1
-                inc r13
-            jmp .begin_while
-        .end_while:
-
-        inc r10
-        jmp .outer_loop_begin
-    .outer_loop_end:
-
+    push rbx
+
+    mov r12, rdi
+    mov r13, rsi
+    mov r14, rdx
+    mov r15, rcx
+    mov rbx, r8
+
+    ## dst[i..i + rcx] += a * b[i], one row per limb of the smaller slice
+    .loop_begin: ## for rbx = r8; rbx > 0; rbx--
+        cmp rbx, 0
+        je .loop_end
+
+        mov rdi, r12
+        mov rsi, r14
+        mov rdx, r15
+        mov rcx, [r13]
+        call addmul_1@PLT
+
+        ## dst[i + rcx] is not written by the previous rows
+        mov [r14 + 8*r15], rax
+
+        lea r13, [r13 + 8]
+        lea r14, [r14 + 8]
+        dec rbx
+        jmp .loop_begin
+    .loop_end:
+
+    pop rbx
     pop r15
     pop r14
     pop r13
@@ -0,0 +1,94 @@
+.intel_syntax noprefix
+.text
+
+.globl      addmul_1_adx
+.globl      mul_two_slices_adx
+
+addmul_1_adx:
+    ## Params:
+    ## rdi - address of the slice
+    ## rsi - dst of the addition (at least len of rdi, can not partially overlap rdi)
+    ## rdx - len of rdi
+    ## rcx - const to multiply rdi by
+    ## Returns:
+    ## rax - carry out of the most significant limb of rsi
+    ## Requires BMI2 and ADX. The high limbs of the products are added in the CF
+    ## chain and dst in the OF chain, the loop only uses jrcxz and lea.
+
+    ## mulx multiplies by rdx
+    mov r8, rdx
+    mov rdx, rcx
+    mov rcx, r8
+
+    xor r9, r9 ## also clears CF and OF
+
+    .loop_begin: ## for rcx = n; rcx > 0; rcx--
+        jrcxz .loop_end
+
+        mulx r10, rax, [rdi]    ## r10:rax = a[i] * c
+
+        adcx rax, r9
+        adox rax, [rsi]
+        mov [rsi], rax
+
+        mov r9, r10
+
+        lea rdi, [rdi + 8]
+        lea rsi, [rsi + 8]
+        lea rcx, [rcx - 1]
+        jmp .loop_begin
+    .loop_end:
+
+    ## both chains end in the carry limb, can not overflow r9
+    mov rax, 0
+    adcx r9, rax
+    adox r9, rax
+
+    mov rax, r9
+    ret
+
+mul_two_slices_adx:
+    ## rdi, rsi - adresses of slices
+    ## rdx      - dst of the multiplication (at least len of rcx + r8, zeroed)
+    ## rcx      - len of the larger slice
+    ## r8       - len of the smaller slice
+
+    push r12
+    push r13
+    push r14
+    push r15
+    push rbx
+
+    mov r12, rdi
+    mov r13, rsi
+    mov r14, rdx
+    mov r15, rcx
+    mov rbx, r8
+
+    ## dst[i..i + rcx] += a * b[i], one row per limb of the smaller slice
+    .rows_begin: ## for rbx = r8; rbx > 0; rbx--
+        cmp rbx, 0
+        je .rows_end
+
+        mov rdi, r12
+        mov rsi, r14
+        mov rdx, r15
+        mov rcx, [r13]
+        call addmul_1_adx@PLT
+
+        ## dst[i + rcx] is not written by the previous rows
+        mov [r14 + 8*r15], rax
+
+        lea r13, [r13 + 8]
+        lea r14, [r14 + 8]
+        dec rbx
+        jmp .rows_begin
+    .rows_end:
+
+    pop rbx
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+
+    ret
@@ -0,0 +1,42 @@
+.intel_syntax noprefix
+.text
+
+.globl      mul_const_adx
+
+mul_const_adx:
+    ## rdi - dst of the multiplication, dst[n] is written only if the carry is not zero
+    ## rsi - const to multiply rdi by
+    ## rdx - len of rdi
+    ## Requires BMI2 and ADX. The carry stays in CF, the loop only uses jrcxz and lea.
+
+    ## mulx multiplies by rdx
+    mov rcx, rdx
+    mov rdx, rsi
+
+    xor r9, r9 ## also clears CF
+
+    .loop_begin: ## for rcx = n; rcx > 0; rcx--
+        jrcxz .loop_end
+
+        mulx r10, rax, [rdi]    ## r10:rax = dst[i] * c
+
+        adcx rax, r9
+        mov [rdi], rax
+
+        mov r9, r10
+
+        lea rdi, [rdi + 8]
+        lea rcx, [rcx - 1]
+        jmp .loop_begin
+    .loop_end:
+
+    ## can not overflow r9
+    mov rax, 0
+    adcx r9, rax
+
+    test r9, r9
+    je .end
+    mov [rdi], r9
+
+    .end:
+    ret
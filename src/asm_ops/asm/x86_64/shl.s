@@ -0,0 +1,41 @@
+.intel_syntax noprefix
+.text
+
+.globl      shl_slice
+
+shl_slice:
+    ## Params:
+    ## rdi - address of the slice
+    ## rsi - dst of the shift (at least len of rdi, can be the same as rdi)
+    ## rdx - len of rdi
+    ## rcx - shift, 0 <= rcx < 64
+    ## Returns:
+    ## rax - bits shifted out of the most significant limb
+
+    xor rax, rax
+
+    cmp rdx, 0
+    je .end
+
+    ## go from the most significant limb so rsi = rdi is not overwritten before read
+    mov r8, [rdi + 8*rdx - 8]
+    shld rax, r8, cl
+
+    .loop_begin: ## for rdx = n - 1; rdx > 0; rdx--
+        dec rdx
+        cmp rdx, 0
+        je .loop_end
+
+        mov r9, [rdi + 8*rdx - 8]
+        shld r8, r9, cl     ## r8 = r8 << cl | high bits of r9
+        mov [rsi + 8*rdx], r8
+        mov r8, r9
+
+        jmp .loop_begin
+    .loop_end:
+
+    shl r8, cl
+    mov [rsi], r8
+
+    .end:
+    ret
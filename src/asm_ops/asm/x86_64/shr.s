@@ -0,0 +1,42 @@
+.intel_syntax noprefix
+.text
+
+.globl      shr_slice
+
+shr_slice:
+    ## Params:
+    ## rdi - address of the slice
+    ## rsi - dst of the shift (at least len of rdi, can be the same as rdi)
+    ## rdx - len of rdi
+    ## rcx - shift, 0 <= rcx < 64
+    ## Returns:
+    ## rax - bits shifted out of the least significant limb, in the high bits
+
+    xor rax, rax
+
+    cmp rdx, 0
+    je .end
+
+    mov r8, [rdi]
+    shrd rax, r8, cl
+
+    xor r10, r10
+    .loop_begin: ## for r10 = 0; r10 < rdx - 1; r10++
+        lea r11, [r10 + 1]
+        cmp rdx, r11
+        jle .loop_end
+
+        mov r9, [rdi + 8*r11]
+        shrd r8, r9, cl     ## r8 = r8 >> cl | low bits of r9
+        mov [rsi + 8*r10], r8
+        mov r8, r9
+
+        mov r10, r11
+        jmp .loop_begin
+    .loop_end:
+
+    shr r8, cl
+    mov [rsi + 8*r10], r8
+
+    .end:
+    ret
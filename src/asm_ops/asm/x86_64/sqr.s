@@ -0,0 +1,118 @@
+.intel_syntax noprefix
+.text
+
+.globl      sqr_slice
+
+sqr_slice:
+    ## Params:
+    ## rdi - address of the slice
+    ## rsi - dst of the square (at least 2 * len of rdi, must not overlap rdi)
+    ## rdx - len of rdi
+
+    push r12
+    push r13
+
+    ## mul overwrites rdx
+    mov r8, rdx
+
+    ## dest = 0
+    xor r9, r9
+    lea r10, [r8 + r8]
+    .zero_loop_begin: ## for r9 = 0; r9 < 2 * r8; r9++
+        cmp r10, r9
+        jle .zero_loop_end
+
+        mov qword ptr [rsi + 8*r9], 0
+
+        inc r9
+        jmp .zero_loop_begin
+    .zero_loop_end:
+
+    ## sum of a[i] * a[j] for i < j, every product computed once
+    xor r9, r9
+    .outer_loop_begin: ## for r9 = 0; r9 < r8; r9++
+        cmp r8, r9
+        jle .outer_loop_end
+
+        mov r11, [rdi + 8*r9]
+        xor r12, r12
+
+        lea r10, [r9 + 1]
+        .inner_loop_begin: ## for r10 = r9 + 1; r10 < r8; r10++
+            cmp r8, r10
+            jle .inner_loop_end
+
+            mov rax, [rdi + 8*r10]
+            mul r11             ## rdx:rax = a[i] * a[j]
+
+            ## add carry and dest[i + j], can not overflow rdx
+            add rax, r12
+            adc rdx, 0
+
+            lea r13, [r9 + r10]
+            add [rsi + 8*r13], rax
+            adc rdx, 0
+
+            mov r12, rdx
+
+            inc r10
+            jmp .inner_loop_begin
+        .inner_loop_end:
+
+        ## dest[i + n] was not written by the previous rows
+        lea r13, [r9 + r8]
+        mov [rsi + 8*r13], r12
+
+        inc r9
+        jmp .outer_loop_begin
+    .outer_loop_end:
+
+    ## dest = 2 * dest
+    xor r9, r9
+    xor r12, r12
+    lea r10, [r8 + r8]
+    .double_loop_begin: ## for r9 = 0; r9 < 2 * r8; r9++
+        cmp r10, r9
+        jle .double_loop_end
+
+        mov rax, [rsi + 8*r9]
+        mov rdx, rax
+        shr rdx, 63
+        shl rax, 1
+        or rax, r12
+        mov [rsi + 8*r9], rax
+        mov r12, rdx
+
+        inc r9
+        jmp .double_loop_begin
+    .double_loop_end:
+
+    ## add a[i] * a[i] to dest[2i], dest[2i + 1]
+    xor r9, r9
+    xor r12, r12
+    .diagonal_loop_begin: ## for r9 = 0; r9 < r8; r9++
+        cmp r8, r9
+        jle .diagonal_loop_end
+
+        mov rax, [rdi + 8*r9]
+        mul rax             ## rdx:rax = a[i] * a[i]
+
+        add rax, r12
+        adc rdx, 0
+
+        lea r13, [r9 + r9]
+        add [rsi + 8*r13], rax
+        adc rdx, 0
+
+        xor r12, r12
+        add [rsi + 8*r13 + 8], rdx
+        adc r12, 0
+
+        inc r9
+        jmp .diagonal_loop_begin
+    .diagonal_loop_end:
+
+    pop r13
+    pop r12
+
+    ret
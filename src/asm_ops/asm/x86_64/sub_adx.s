@@ -0,0 +1,53 @@
+.intel_syntax noprefix
+.text
+
+.globl      sub_two_slices_adx
+
+sub_two_slices_adx:
+    ## rdi, rsi - adresses of slices
+    ## rdx      - dst of the substraction (at least len of bigger slice)
+    ## rcx      - len of the larger slice
+    ## r8       - len of the smaller slice
+    ## Requires ADX. Computes a + !b + 1 with an adcx chain, so CF holds the
+    ## inverted borrow. The loops only use jrcxz, lea and not, which keep CF.
+
+    ## limbs only in the larger slice
+    sub rcx, r8
+    mov r9, rcx
+    mov rcx, r8
+
+    mov rax, -1 ## !0 for the limbs only in the larger slice
+    stc ## the + 1, no borrow yet
+
+    .for_begin: ## for rcx = r8; rcx > 0; rcx--
+        jrcxz .for_end
+
+        mov r10, [rsi]
+        not r10
+        adcx r10, [rdi]
+        mov [rdx], r10
+
+        lea rdi, [rdi + 8]
+        lea rsi, [rsi + 8]
+        lea rdx, [rdx + 8]
+        lea rcx, [rcx - 1]
+        jmp .for_begin
+    .for_end:
+
+    mov rcx, r9
+
+    ## loop only through bigger slice
+    .for_one_slice_begin: ## for rcx = r9; rcx > 0; rcx--
+        jrcxz .for_one_slice_end
+
+        mov r10, [rdi]
+        adcx r10, rax
+        mov [rdx], r10
+
+        lea rdi, [rdi + 8]
+        lea rdx, [rdx + 8]
+        lea rcx, [rcx - 1]
+        jmp .for_one_slice_begin
+    .for_one_slice_end:
+
+    ret
@@ -0,0 +1,244 @@
+// Picks between the generic x86_64 kernels and their BMI2/ADX variants once, on
+// the first call, and forwards every call to the selected table.
+
+use crate::IntLimb;
+use std::env;
+use std::sync::OnceLock;
+
+// Forces a variant, for testing the one the CPU would not pick by itself.
+pub(crate) const KERNELS_VAR: &str = "BIG_NUMBERS_KERNELS";
+
+type TwoSlicesKernel =
+    unsafe extern "C" fn(*const IntLimb, *const IntLimb, *mut IntLimb, IntLimb, IntLimb);
+type MulConstKernel = unsafe extern "C" fn(*mut IntLimb, IntLimb, IntLimb);
+
+extern "C" {
+    #[link_name = "add_two_slices"]
+    fn add_two_slices_generic(
+        a: *const IntLimb,
+        b: *const IntLimb,
+        dest: *mut IntLimb,
+        n1: IntLimb,
+        n2: IntLimb,
+    );
+    #[link_name = "sub_two_slices"]
+    fn sub_two_slices_generic(
+        a: *const IntLimb,
+        b: *const IntLimb,
+        dest: *mut IntLimb,
+        n1: IntLimb,
+        n2: IntLimb,
+    );
+    #[link_name = "mul_two_slices"]
+    fn mul_two_slices_generic(
+        a: *const IntLimb,
+        b: *const IntLimb,
+        dest: *mut IntLimb,
+        n1: IntLimb,
+        n2: IntLimb,
+    );
+    #[link_name = "mul_const"]
+    fn mul_const_generic(dest: *mut IntLimb, c: IntLimb, n: IntLimb);
+
+    fn add_two_slices_adx(
+        a: *const IntLimb,
+        b: *const IntLimb,
+        dest: *mut IntLimb,
+        n1: IntLimb,
+        n2: IntLimb,
+    );
+    fn sub_two_slices_adx(
+        a: *const IntLimb,
+        b: *const IntLimb,
+        dest: *mut IntLimb,
+        n1: IntLimb,
+        n2: IntLimb,
+    );
+    fn mul_two_slices_adx(
+        a: *const IntLimb,
+        b: *const IntLimb,
+        dest: *mut IntLimb,
+        n1: IntLimb,
+        n2: IntLimb,
+    );
+    fn mul_const_adx(dest: *mut IntLimb, c: IntLimb, n: IntLimb);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Variant {
+    Generic,
+    Adx,
+}
+
+struct Kernels {
+    add_two_slices: TwoSlicesKernel,
+    sub_two_slices: TwoSlicesKernel,
+    mul_two_slices: TwoSlicesKernel,
+    mul_const: MulConstKernel,
+}
+
+static GENERIC: Kernels = Kernels {
+    add_two_slices: add_two_slices_generic,
+    sub_two_slices: sub_two_slices_generic,
+    mul_two_slices: mul_two_slices_generic,
+    mul_const: mul_const_generic,
+};
+
+static ADX: Kernels = Kernels {
+    add_two_slices: add_two_slices_adx,
+    sub_two_slices: sub_two_slices_adx,
+    mul_two_slices: mul_two_slices_adx,
+    mul_const: mul_const_adx,
+};
+
+static SELECTED: OnceLock<&'static Kernels> = OnceLock::new();
+
+fn adx_supported() -> bool {
+    is_x86_feature_detected!("bmi2") && is_x86_feature_detected!("adx")
+}
+
+// Panics on unknown names and on forcing ADX without CPU support, which would
+// otherwise end in an illegal instruction.
+fn select(forced: Option<&str>, adx_supported: bool) -> Variant {
+    match forced {
+        None if adx_supported => Variant::Adx,
+        None | Some("generic") => Variant::Generic,
+        Some("adx") if adx_supported => Variant::Adx,
+        Some("adx") => panic!(
+            "{}=adx, but the CPU does not support BMI2 and ADX",
+            KERNELS_VAR
+        ),
+        Some(other) => panic!("{}={} is not one of generic, adx", KERNELS_VAR, other),
+    }
+}
+
+fn kernels() -> &'static Kernels {
+    SELECTED.get_or_init(|| {
+        let forced = env::var(KERNELS_VAR).ok();
+        match select(forced.as_deref(), adx_supported()) {
+            Variant::Generic => &GENERIC,
+            Variant::Adx => &ADX,
+        }
+    })
+}
+
+pub(crate) unsafe fn add_two_slices(
+    a: *const IntLimb,
+    b: *const IntLimb,
+    dest: *mut IntLimb,
+    n1: IntLimb,
+    n2: IntLimb,
+) {
+    (kernels().add_two_slices)(a, b, dest, n1, n2)
+}
+
+pub(crate) unsafe fn sub_two_slices(
+    a: *const IntLimb,
+    b: *const IntLimb,
+    dest: *mut IntLimb,
+    n1: IntLimb,
+    n2: IntLimb,
+) {
+    (kernels().sub_two_slices)(a, b, dest, n1, n2)
+}
+
+pub(crate) unsafe fn mul_two_slices(
+    a: *const IntLimb,
+    b: *const IntLimb,
+    dest: *mut IntLimb,
+    n1: IntLimb,
+    n2: IntLimb,
+) {
+    (kernels().mul_two_slices)(a, b, dest, n1, n2)
+}
+
+pub(crate) unsafe fn mul_const(dest: *mut IntLimb, c: IntLimb, n: IntLimb) {
+    (kernels().mul_const)(dest, c, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples() -> Vec<Vec<IntLimb>> {
+        let x = IntLimb::MAX;
+        let mut state: IntLimb = 0x9e3779b97f4a7c15;
+        let mut random = |n: usize| {
+            (0..n)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut samples = vec![vec![], vec![0], vec![1], vec![x], vec![x; 7], vec![x, 0, x]];
+        for n in [1, 2, 3, 8, 17] {
+            samples.push(random(n));
+        }
+        samples
+    }
+
+    #[test]
+    fn select_variant() {
+        assert_eq!(Variant::Adx, select(None, true));
+        assert_eq!(Variant::Generic, select(None, false));
+        assert_eq!(Variant::Generic, select(Some("generic"), true));
+        assert_eq!(Variant::Adx, select(Some("adx"), true));
+    }
+
+    #[test]
+    #[should_panic]
+    fn select_unsupported_adx() {
+        select(Some("adx"), false);
+    }
+
+    #[test]
+    #[should_panic]
+    fn select_unknown_variant() {
+        select(Some("avx512"), true);
+    }
+
+    #[test]
+    fn adx_matches_generic() {
+        // Nothing to compare against without the CPU support, the ADX kernels can't run.
+        if !adx_supported() {
+            return;
+        }
+
+        let samples = samples();
+        for a in samples.iter() {
+            for b in samples.iter().filter(|b| b.len() <= a.len()) {
+                let (n1, n2) = (a.len(), b.len());
+                let kernels: [(TwoSlicesKernel, TwoSlicesKernel, usize, bool); 3] = [
+                    (add_two_slices_generic, add_two_slices_adx, n1 + 1, false),
+                    (sub_two_slices_generic, sub_two_slices_adx, n1, false),
+                    (mul_two_slices_generic, mul_two_slices_adx, n1 + n2, true),
+                ];
+
+                for (generic, adx, len, zeroed) in kernels {
+                    let fill = if zeroed { 0 } else { 5 };
+                    let (mut expected, mut actual) = (vec![fill; len], vec![fill; len]);
+                    unsafe {
+                        generic(a.as_ptr(), b.as_ptr(), expected.as_mut_ptr(), n1, n2);
+                        adx(a.as_ptr(), b.as_ptr(), actual.as_mut_ptr(), n1, n2);
+                    }
+                    assert_eq!(expected, actual, "{:?} {:?}", a, b);
+                }
+            }
+
+            for c in [0, 1, 3, IntLimb::MAX, 0x123456789abcdef] {
+                let (mut expected, mut actual) = (a.clone(), a.clone());
+                expected.push(7);
+                actual.push(7);
+                unsafe {
+                    mul_const_generic(expected.as_mut_ptr(), c, a.len());
+                    mul_const_adx(actual.as_mut_ptr(), c, a.len());
+                }
+                assert_eq!(expected, actual, "{:?} * {}", a, c);
+            }
+        }
+    }
+}
@@ -1,6 +1,21 @@
+#[cfg(asm_kernels)]
 use crate::IntLimb;
 
+pub(crate) mod portable;
+
+#[cfg(not(asm_kernels))]
+pub(crate) use portable::*;
+
+// x86_64 has BMI2/ADX variants of some kernels, chosen at runtime.
+#[cfg(all(asm_kernels, target_arch = "x86_64"))]
+mod dispatch;
+
+#[cfg(all(asm_kernels, target_arch = "x86_64"))]
+pub(crate) use dispatch::{add_two_slices, mul_const, mul_two_slices, sub_two_slices};
+
+#[cfg(asm_kernels)]
 extern "C" {
+    #[cfg(not(target_arch = "x86_64"))]
     pub(crate) fn add_two_slices(
         a: *const IntLimb,
         b: *const IntLimb,
@@ -8,6 +23,7 @@ extern "C" {
         n1: IntLimb,
         n2: IntLimb,
     );
+    #[cfg(not(target_arch = "x86_64"))]
     pub(crate) fn sub_two_slices(
         a: *const IntLimb,
         b: *const IntLimb,
@@ -15,6 +31,7 @@ extern "C" {
         n1: IntLimb,
         n2: IntLimb,
     );
+    #[cfg(not(target_arch = "x86_64"))]
     pub(crate) fn mul_two_slices(
         a: *const IntLimb,
         b: *const IntLimb,
@@ -22,78 +39,55 @@ extern "C" {
         n1: IntLimb,
         n2: IntLimb,
     );
+    pub(crate) fn sqr_slice(a: *const IntLimb, dest: *mut IntLimb, n: IntLimb);
 }
 
+#[cfg(asm_kernels)]
 extern "C" {
     pub(crate) fn add_const(dest: *mut IntLimb, c: IntLimb);
     pub(crate) fn sub_const(dest: *mut IntLimb, c: IntLimb);
+    #[cfg(not(target_arch = "x86_64"))]
     pub(crate) fn mul_const(dest: *mut IntLimb, c: IntLimb, n: IntLimb);
     pub(crate) fn div_const(dest: *mut IntLimb, c: IntLimb, n: IntLimb) -> IntLimb;
+    pub(crate) fn addmul_1(
+        a: *const IntLimb,
+        dest: *mut IntLimb,
+        n: IntLimb,
+        c: IntLimb,
+    ) -> IntLimb;
+    pub(crate) fn submul_1(
+        a: *const IntLimb,
+        dest: *mut IntLimb,
+        n: IntLimb,
+        c: IntLimb,
+    ) -> IntLimb;
 }
 
+#[cfg(asm_kernels)]
 extern "C" {
-    pub(crate) fn cmp_slices(a: *const IntLimb, b: *const IntLimb, n: IntLimb) -> i32;
+    pub(crate) fn shl_slice(
+        a: *const IntLimb,
+        dest: *mut IntLimb,
+        n: IntLimb,
+        shift: IntLimb,
+    ) -> IntLimb;
+    pub(crate) fn shr_slice(
+        a: *const IntLimb,
+        dest: *mut IntLimb,
+        n: IntLimb,
+        shift: IntLimb,
+    ) -> IntLimb;
 }
 
-pub mod wrapped_ops {
-    use super::*;
-
-    pub(crate) fn unsafe_add_two_slices(a: &[IntLimb], b: &[IntLimb], d: &mut [IntLimb]) {
-        let (n, m) = (a.len(), b.len());
-        assert!(d.len() >= usize::max(n, m) + 1);
-        unsafe {
-            if n > m {
-                super::add_two_slices(
-                    a.as_ptr(),
-                    b.as_ptr(),
-                    d.as_mut_ptr(),
-                    n as IntLimb,
-                    m as IntLimb,
-                );
-            } else {
-                super::add_two_slices(
-                    b.as_ptr(),
-                    a.as_ptr(),
-                    d.as_mut_ptr(),
-                    m as IntLimb,
-                    n as IntLimb,
-                );
-            }
-        }
-    }
-
-    pub(crate) fn unsafe_sub_two_slices(a: &[IntLimb], b: &[IntLimb], d: &mut [IntLimb]) {
-        let (n, m) = (a.len(), b.len());
-        assert!(d.len() >= usize::max(n, m) && n >= m);
-        unsafe {
-            super::sub_two_slices(
-                a.as_ptr(),
-                b.as_ptr(),
-                d.as_mut_ptr(),
-                n as IntLimb,
-                m as IntLimb,
-            );
-        }
-    }
-
-    pub(crate) fn unsafe_mul_two_slices(a: &[IntLimb], b: &[IntLimb], d: &mut [IntLimb]) {
-        let (n, m) = (a.len(), b.len());
-        assert!(d.len() >= n + m);
-        unsafe {
-            super::mul_two_slices(
-                a.as_ptr(),
-                b.as_ptr(),
-                d.as_mut_ptr(),
-                n as IntLimb,
-                m as IntLimb,
-            );
-        }
-    }
+#[cfg(asm_kernels)]
+extern "C" {
+    pub(crate) fn cmp_slices(a: *const IntLimb, b: *const IntLimb, n: IntLimb) -> i32;
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::IntLimb;
 
     #[test]
     fn test_adding_slices_even_len() {
@@ -125,7 +119,19 @@ mod tests {
         let mut dest = [0, 0, 0, 0, 0, 0];
 
         let c = [0, 0, 0, 0, 0, 1];
-        unsafe { add_two_slices(a.as_ptr(), b.as_ptr(), dest.as_mut_ptr(), 6, 5) }
+        unsafe { add_two_slices(a.as_ptr(), b.as_ptr(), dest.as_mut_ptr(), 5, 4) }
+        assert_eq!(c, dest);
+    }
+
+    #[test]
+    fn test_adding_slices_carry_through_larger_slice() {
+        let x = IntLimb::MAX;
+        let a = [x, x, 5];
+        let b = [1];
+        let mut dest = [0, 0, 0, 0];
+
+        let c = [0, 0, 6, 0];
+        unsafe { add_two_slices(a.as_ptr(), b.as_ptr(), dest.as_mut_ptr(), 3, 1) }
         assert_eq!(c, dest);
     }
 
@@ -217,6 +223,74 @@ mod tests {
         assert_eq!(c, dest);
     }
 
+    #[test]
+    fn test_sqr_slice() {
+        let a = [1, 2, 3, 4, 6];
+        let mut dest = [7; 10];
+
+        let c = [1, 4, 10, 20, 37, 48, 52, 48, 36, 0];
+        unsafe { sqr_slice(a.as_ptr(), dest.as_mut_ptr(), 5) }
+        assert_eq!(c, dest);
+    }
+
+    #[test]
+    fn test_sqr_slice_of_nines() {
+        let x = IntLimb::MAX;
+        let a = [x, x, x, x, x];
+        let mut dest = [0; 10];
+
+        let c = [1, 0, 0, 0, 0, x - 1, x, x, x, x];
+        unsafe { sqr_slice(a.as_ptr(), dest.as_mut_ptr(), 5) }
+        assert_eq!(c, dest);
+    }
+
+    #[test]
+    fn test_sqr_slice_single_limb() {
+        let x = IntLimb::MAX;
+        let a = [x];
+        let mut dest = [0, 0];
+
+        unsafe { sqr_slice(a.as_ptr(), dest.as_mut_ptr(), 1) }
+        assert_eq!([1, x - 1], dest);
+    }
+
+    #[test]
+    fn test_addmul_1() {
+        let x = IntLimb::MAX;
+        let a = [x, 2, x];
+        let mut dest = [1, x, 5, 9];
+
+        // the last limb is not touched, the carry is returned instead
+        let c = [2, x - 4, 9, 9];
+        let carry = unsafe { addmul_1(a.as_ptr(), dest.as_mut_ptr(), 3, x) };
+        assert_eq!(c, dest);
+        assert_eq!(x - 1, carry);
+    }
+
+    #[test]
+    fn test_submul_1() {
+        let a = [3, 1];
+        let mut dest = [10, 3, 7];
+
+        let c = [4, 1, 7];
+        let borrow = unsafe { submul_1(a.as_ptr(), dest.as_mut_ptr(), 2, 2) };
+        assert_eq!(c, dest);
+        assert_eq!(0, borrow);
+    }
+
+    #[test]
+    fn test_submul_1_borrow() {
+        let x = IntLimb::MAX;
+        let a = [x, x];
+        let mut dest = [0, 0];
+
+        // 0 - (B^2 - 1) * x = x - x * B^2
+        let c = [x, 0];
+        let borrow = unsafe { submul_1(a.as_ptr(), dest.as_mut_ptr(), 2, x) };
+        assert_eq!(c, dest);
+        assert_eq!(x, borrow);
+    }
+
     #[test]
     fn test_adding_const() {
         let x = IntLimb::MAX;
@@ -293,6 +367,53 @@ mod tests {
         assert_eq!(c, a);
     }
 
+    #[test]
+    fn test_shl_slice() {
+        let x = IntLimb::MAX;
+        let a = [x, 1, 1 << (IntLimb::BITS - 1)];
+        let mut dest = [0, 0, 0];
+
+        let c = [x << 4, 0x1f, 0];
+        let carry = unsafe { shl_slice(a.as_ptr(), dest.as_mut_ptr(), 3, 4) };
+        assert_eq!(c, dest);
+        assert_eq!(8, carry);
+    }
+
+    #[test]
+    fn test_shl_slice_in_place_by_zero() {
+        let mut a = [1, 2, 3];
+
+        let c = [1, 2, 3];
+        let p = a.as_mut_ptr();
+        let carry = unsafe { shl_slice(p, p, 3, 0) };
+        assert_eq!(c, a);
+        assert_eq!(0, carry);
+    }
+
+    #[test]
+    fn test_shr_slice() {
+        let x = IntLimb::MAX;
+        let a = [0x13, x, 0x10];
+        let mut dest = [0, 0, 0];
+
+        let c = [(0xf << (IntLimb::BITS - 4)) | 0x1, x >> 4, 0x1];
+        let out = unsafe { shr_slice(a.as_ptr(), dest.as_mut_ptr(), 3, 4) };
+        assert_eq!(c, dest);
+        assert_eq!(3 << (IntLimb::BITS - 4), out);
+    }
+
+    #[test]
+    fn test_shr_slice_in_place() {
+        let mut a = [1, 3, 4];
+
+        let top = 1 << (IntLimb::BITS - 1);
+        let c = [top, 1, 2];
+        let p = a.as_mut_ptr();
+        let out = unsafe { shr_slice(p, p, 3, 1) };
+        assert_eq!(c, a);
+        assert_eq!(top, out);
+    }
+
     #[test]
     fn test_cmp_slices_1() {
         let a = [1, 2, 3, 4, 6];
@@ -385,4 +506,166 @@ mod tests {
         assert_eq!(c, a);
         assert_eq!(1537228672809129304, remainder);
     }
+
+    // Runs the assembly kernels and the portable ones side by side on the same inputs.
+    #[cfg(asm_kernels)]
+    mod against_portable {
+        use super::super::portable;
+        use super::*;
+
+        fn samples() -> Vec<Vec<IntLimb>> {
+            let x = IntLimb::MAX;
+            let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+            let mut random = |len: usize| -> Vec<IntLimb> {
+                (0..len)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        state as IntLimb
+                    })
+                    .collect()
+            };
+
+            vec![
+                vec![1],
+                vec![x],
+                vec![x; 7],
+                vec![0, 0, 1 << 63],
+                vec![x, 0, x, 0, 1],
+                random(1),
+                random(6),
+                random(33),
+            ]
+        }
+
+        #[test]
+        fn two_slice_kernels() {
+            for a in samples().iter() {
+                for b in samples().iter().filter(|b| b.len() <= a.len()) {
+                    let (n, m) = (a.len() as IntLimb, b.len() as IntLimb);
+
+                    let (mut asm, mut rust) = (vec![0; a.len() + 1], vec![0; a.len() + 1]);
+                    unsafe {
+                        add_two_slices(a.as_ptr(), b.as_ptr(), asm.as_mut_ptr(), n, m);
+                        portable::add_two_slices(a.as_ptr(), b.as_ptr(), rust.as_mut_ptr(), n, m);
+                    }
+                    assert_eq!(asm, rust);
+
+                    let (mut asm, mut rust) = (vec![0; a.len()], vec![0; a.len()]);
+                    unsafe {
+                        sub_two_slices(a.as_ptr(), b.as_ptr(), asm.as_mut_ptr(), n, m);
+                        portable::sub_two_slices(a.as_ptr(), b.as_ptr(), rust.as_mut_ptr(), n, m);
+                    }
+                    assert_eq!(asm, rust);
+
+                    let (mut asm, mut rust) =
+                        (vec![0; a.len() + b.len()], vec![0; a.len() + b.len()]);
+                    unsafe {
+                        mul_two_slices(a.as_ptr(), b.as_ptr(), asm.as_mut_ptr(), n, m);
+                        portable::mul_two_slices(a.as_ptr(), b.as_ptr(), rust.as_mut_ptr(), n, m);
+                    }
+                    assert_eq!(asm, rust);
+
+                    let (mut asm, mut rust) = (a.clone(), a.clone());
+                    let (c_asm, c_rust) = unsafe {
+                        (
+                            addmul_1(b.as_ptr(), asm.as_mut_ptr(), m, a[0]),
+                            portable::addmul_1(b.as_ptr(), rust.as_mut_ptr(), m, a[0]),
+                        )
+                    };
+                    assert_eq!((asm, c_asm), (rust, c_rust));
+
+                    let (mut asm, mut rust) = (a.clone(), a.clone());
+                    let (c_asm, c_rust) = unsafe {
+                        (
+                            submul_1(b.as_ptr(), asm.as_mut_ptr(), m, a[0]),
+                            portable::submul_1(b.as_ptr(), rust.as_mut_ptr(), m, a[0]),
+                        )
+                    };
+                    assert_eq!((asm, c_asm), (rust, c_rust));
+
+                    let (asm, rust) = unsafe {
+                        (
+                            cmp_slices(a.as_ptr(), b.as_ptr(), m),
+                            portable::cmp_slices(a.as_ptr(), b.as_ptr(), m),
+                        )
+                    };
+                    assert_eq!(asm, rust);
+                }
+            }
+        }
+
+        #[test]
+        fn single_slice_kernels() {
+            for a in samples().iter() {
+                let n = a.len() as IntLimb;
+
+                let (mut asm, mut rust) = (vec![0; 2 * a.len()], vec![0; 2 * a.len()]);
+                unsafe {
+                    sqr_slice(a.as_ptr(), asm.as_mut_ptr(), n);
+                    portable::sqr_slice(a.as_ptr(), rust.as_mut_ptr(), n);
+                }
+                assert_eq!(asm, rust);
+
+                for &c in [1, 3, 1 << 63, IntLimb::MAX].iter() {
+                    let (mut asm, mut rust) = (a.clone(), a.clone());
+                    asm.push(0);
+                    rust.push(0);
+                    unsafe {
+                        add_const(asm.as_mut_ptr(), c);
+                        portable::add_const(rust.as_mut_ptr(), c);
+                    }
+                    assert_eq!(asm, rust);
+
+                    let (mut asm, mut rust) = (a.clone(), a.clone());
+                    asm.push(0);
+                    rust.push(0);
+                    unsafe {
+                        mul_const(asm.as_mut_ptr(), c, n);
+                        portable::mul_const(rust.as_mut_ptr(), c, n);
+                    }
+                    assert_eq!(asm, rust);
+
+                    let (mut asm, mut rust) = (a.clone(), a.clone());
+                    let (r_asm, r_rust) = unsafe {
+                        (
+                            div_const(asm.as_mut_ptr(), c, n),
+                            portable::div_const(rust.as_mut_ptr(), c, n),
+                        )
+                    };
+                    assert_eq!((asm, r_asm), (rust, r_rust));
+                }
+
+                if a.iter().any(|&x| x != 0) {
+                    let (mut asm, mut rust) = (a.clone(), a.clone());
+                    unsafe {
+                        sub_const(asm.as_mut_ptr(), 1);
+                        portable::sub_const(rust.as_mut_ptr(), 1);
+                    }
+                    assert_eq!(asm, rust);
+                }
+
+                for shift in [0, 1, 13, 63].iter() {
+                    let (mut asm, mut rust) = (a.clone(), a.clone());
+                    let (out_asm, out_rust) = unsafe {
+                        (
+                            shl_slice(asm.as_ptr(), asm.as_mut_ptr(), n, *shift),
+                            portable::shl_slice(rust.as_ptr(), rust.as_mut_ptr(), n, *shift),
+                        )
+                    };
+                    assert_eq!((asm, out_asm), (rust, out_rust));
+
+                    let (mut asm, mut rust) = (a.clone(), a.clone());
+                    let (out_asm, out_rust) = unsafe {
+                        (
+                            shr_slice(asm.as_ptr(), asm.as_mut_ptr(), n, *shift),
+                            portable::shr_slice(rust.as_ptr(), rust.as_mut_ptr(), n, *shift),
+                        )
+                    };
+                    assert_eq!((asm, out_asm), (rust, out_rust));
+                }
+            }
+        }
+    }
 }
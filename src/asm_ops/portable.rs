@@ -0,0 +1,238 @@
+// Pure Rust versions of the assembly kernels with the same signatures and semantics,
+// used on targets without an assembly backend or with the `portable` feature.
+//
+// Every kernel reads a limb of its operands before writing the same limb of the
+// destination, so in-place calls behave like the assembly ones.
+
+use crate::IntLimb;
+
+const LIMB_BITS: u32 = IntLimb::BITS;
+
+#[inline]
+fn split(x: u128) -> (IntLimb, IntLimb) {
+    (x as IntLimb, (x >> LIMB_BITS) as IntLimb)
+}
+
+pub(crate) unsafe fn add_two_slices(
+    a: *const IntLimb,
+    b: *const IntLimb,
+    dest: *mut IntLimb,
+    n1: IntLimb,
+    n2: IntLimb,
+) {
+    let mut carry = 0;
+    for i in 0..n1 {
+        let y = if i < n2 { *b.add(i) } else { 0 };
+        let (low, high) = split(*a.add(i) as u128 + y as u128 + carry as u128);
+        *dest.add(i) = low;
+        carry = high;
+    }
+    *dest.add(n1) = carry;
+}
+
+pub(crate) unsafe fn sub_two_slices(
+    a: *const IntLimb,
+    b: *const IntLimb,
+    dest: *mut IntLimb,
+    n1: IntLimb,
+    n2: IntLimb,
+) {
+    let mut borrow = false;
+    for i in 0..n1 {
+        let y = if i < n2 { *b.add(i) } else { 0 };
+        let (v1, b1) = (*a.add(i)).overflowing_sub(y);
+        let (v2, b2) = v1.overflowing_sub(borrow as IntLimb);
+        *dest.add(i) = v2;
+        borrow = b1 || b2;
+    }
+}
+
+pub(crate) unsafe fn mul_two_slices(
+    a: *const IntLimb,
+    b: *const IntLimb,
+    dest: *mut IntLimb,
+    n1: IntLimb,
+    n2: IntLimb,
+) {
+    for i in 0..n2 {
+        let row = dest.add(i);
+        *row.add(n1) = addmul_1(a, row, n1, *b.add(i));
+    }
+}
+
+pub(crate) unsafe fn sqr_slice(a: *const IntLimb, dest: *mut IntLimb, n: IntLimb) {
+    for i in 0..2 * n {
+        *dest.add(i) = 0;
+    }
+
+    // Products a[i] * a[j] for i < j once, then doubled.
+    for i in 0..n {
+        let row = dest.add(2 * i + 1);
+        *row.add(n - i - 1) = addmul_1(a.add(i + 1), row, n - i - 1, *a.add(i));
+    }
+
+    let mut high_bit = 0;
+    for i in 0..2 * n {
+        let x = *dest.add(i);
+        *dest.add(i) = (x << 1) | high_bit;
+        high_bit = x >> (LIMB_BITS - 1);
+    }
+
+    let mut carry = 0;
+    for i in 0..n {
+        let x = *a.add(i) as u128;
+        let (low, high) = split(x * x);
+
+        let (low, c1) = split(*dest.add(2 * i) as u128 + low as u128 + carry as u128);
+        *dest.add(2 * i) = low;
+
+        let (high, c2) = split(*dest.add(2 * i + 1) as u128 + high as u128 + c1 as u128);
+        *dest.add(2 * i + 1) = high;
+        carry = c2;
+    }
+}
+
+pub(crate) unsafe fn addmul_1(
+    a: *const IntLimb,
+    dest: *mut IntLimb,
+    n: IntLimb,
+    c: IntLimb,
+) -> IntLimb {
+    let mut carry = 0;
+    for i in 0..n {
+        let x = *a.add(i) as u128 * c as u128 + *dest.add(i) as u128 + carry as u128;
+        let (low, high) = split(x);
+        *dest.add(i) = low;
+        carry = high;
+    }
+    carry
+}
+
+pub(crate) unsafe fn submul_1(
+    a: *const IntLimb,
+    dest: *mut IntLimb,
+    n: IntLimb,
+    c: IntLimb,
+) -> IntLimb {
+    let mut borrow = 0;
+    for i in 0..n {
+        let (low, high) = split(*a.add(i) as u128 * c as u128 + borrow as u128);
+        let (v, b) = (*dest.add(i)).overflowing_sub(low);
+        *dest.add(i) = v;
+        borrow = high + b as IntLimb;
+    }
+    borrow
+}
+
+pub(crate) unsafe fn add_const(dest: *mut IntLimb, c: IntLimb) {
+    let (mut i, mut carry) = (0, c);
+    loop {
+        let (v, overflow) = (*dest.add(i)).overflowing_add(carry);
+        *dest.add(i) = v;
+        if !overflow {
+            break;
+        }
+        carry = 1;
+        i += 1;
+    }
+}
+
+pub(crate) unsafe fn sub_const(dest: *mut IntLimb, c: IntLimb) {
+    let (mut i, mut borrow) = (0, c);
+    loop {
+        let (v, overflow) = (*dest.add(i)).overflowing_sub(borrow);
+        *dest.add(i) = v;
+        if !overflow {
+            break;
+        }
+        borrow = 1;
+        i += 1;
+    }
+}
+
+pub(crate) unsafe fn mul_const(dest: *mut IntLimb, c: IntLimb, n: IntLimb) {
+    let mut carry = 0;
+    for i in 0..n {
+        let (low, high) = split(*dest.add(i) as u128 * c as u128 + carry as u128);
+        *dest.add(i) = low;
+        carry = high;
+    }
+
+    if carry != 0 {
+        *dest.add(n) = carry;
+    }
+}
+
+// `dest` is most significant limb first.
+pub(crate) unsafe fn div_const(dest: *mut IntLimb, c: IntLimb, n: IntLimb) -> IntLimb {
+    let mut remainder = 0;
+    for i in 0..n {
+        let x = ((remainder as u128) << LIMB_BITS) | *dest.add(i) as u128;
+        *dest.add(i) = (x / c as u128) as IntLimb;
+        remainder = (x % c as u128) as IntLimb;
+    }
+    remainder
+}
+
+pub(crate) unsafe fn shl_slice(
+    a: *const IntLimb,
+    dest: *mut IntLimb,
+    n: IntLimb,
+    shift: IntLimb,
+) -> IntLimb {
+    if shift == 0 {
+        for i in 0..n {
+            *dest.add(i) = *a.add(i);
+        }
+        return 0;
+    }
+
+    // From the most significant limb so dest = a is not overwritten before it is read.
+    let mut out = 0;
+    for i in (0..n).rev() {
+        let x = *a.add(i);
+        if i + 1 < n {
+            *dest.add(i + 1) |= x >> (LIMB_BITS - shift as u32);
+        } else {
+            out = x >> (LIMB_BITS - shift as u32);
+        }
+        *dest.add(i) = x << shift;
+    }
+    out
+}
+
+pub(crate) unsafe fn shr_slice(
+    a: *const IntLimb,
+    dest: *mut IntLimb,
+    n: IntLimb,
+    shift: IntLimb,
+) -> IntLimb {
+    if shift == 0 {
+        for i in 0..n {
+            *dest.add(i) = *a.add(i);
+        }
+        return 0;
+    }
+
+    let mut out = 0;
+    for i in 0..n {
+        let x = *a.add(i);
+        if i > 0 {
+            *dest.add(i - 1) |= x << (LIMB_BITS - shift as u32);
+        } else {
+            out = x << (LIMB_BITS - shift as u32);
+        }
+        *dest.add(i) = x >> shift;
+    }
+    out
+}
+
+pub(crate) unsafe fn cmp_slices(a: *const IntLimb, b: *const IntLimb, n: IntLimb) -> i32 {
+    for i in (0..n).rev() {
+        let (x, y) = (*a.add(i), *b.add(i));
+        if x != y {
+            return if x > y { 1 } else { -1 };
+        }
+    }
+    0
+}
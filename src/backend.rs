@@ -0,0 +1,364 @@
+//! Limb kernel sets the arithmetic is built on.
+//!
+//! The algorithms in the crate are generic over [`LimbBackend`], so a new set of kernels
+//! only has to implement the trait to be run and benchmarked against the others, either
+//! directly or through [`Int::mul_with_backend`](crate::int::Int::mul_with_backend) and
+//! [`Int::div_rem_with_backend`](crate::int::Int::div_rem_with_backend). Every other
+//! operation on [`Int`](crate::int::Int) uses [`Native`].
+
+use std::cmp::Ordering;
+
+use crate::asm_ops;
+use crate::asm_ops::portable;
+use crate::IntLimb;
+
+/// Kernels over little-endian limb slices, unless stated otherwise. Slices shorter than
+/// documented panic.
+pub trait LimbBackend {
+    /// `dest = a + b`, with the carry in `dest[max(a.len(), b.len())]`.
+    fn add(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]);
+
+    /// `a[..n] += b` for `n = a.len() - 1`, with the carry in `a[n]`.
+    fn add_assign(a: &mut [IntLimb], b: &[IntLimb]);
+
+    /// `dest = a - b` modulo `B^a.len()`, `a` must not be shorter than `b`.
+    fn sub(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]);
+
+    /// `a -= b` modulo `B^a.len()`.
+    fn sub_assign(a: &mut [IntLimb], b: &[IntLimb]);
+
+    /// `b = a - b` modulo `B^a.len()`, with `b` as long as `a`.
+    fn sub_from(a: &[IntLimb], b: &mut [IntLimb]);
+
+    /// `dest = a * b`, `dest` has to be zeroed and hold `a.len() + b.len()` limbs.
+    fn mul(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]);
+
+    /// `dest = a * a` over `2 * a.len()` limbs.
+    fn sqr(a: &[IntLimb], dest: &mut [IntLimb]);
+
+    /// `dest[..a.len()] += a * c`, returns the carry.
+    fn addmul_1(a: &[IntLimb], dest: &mut [IntLimb], c: IntLimb) -> IntLimb;
+
+    /// `dest[..a.len()] -= a * c`, returns the borrow.
+    fn submul_1(a: &[IntLimb], dest: &mut [IntLimb], c: IntLimb) -> IntLimb;
+
+    /// `a += c`, returns the carry out of `a`.
+    fn add_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb;
+
+    /// `a -= c`, returns the borrow out of `a`.
+    fn sub_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb;
+
+    /// `a[..n] *= c` for `n = a.len() - 1`, with the carry in `a[n]`.
+    fn mul_limb(a: &mut [IntLimb], c: IntLimb);
+
+    /// `a /= c` with `a` most significant limb first, returns the remainder.
+    fn div_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb;
+
+    /// `dest = a << shift` over `a.len()` limbs, returns the bits shifted out.
+    fn shl(a: &[IntLimb], dest: &mut [IntLimb], shift: u32) -> IntLimb;
+
+    /// `dest = a >> shift` over `a.len()` limbs, returns the bits shifted out in the
+    /// high bits of the limb.
+    fn shr(a: &[IntLimb], dest: &mut [IntLimb], shift: u32) -> IntLimb;
+
+    /// Compares slices of the same length.
+    fn cmp(a: &[IntLimb], b: &[IntLimb]) -> Ordering;
+}
+
+/// The assembly kernels of the target, or the portable ones where there are none.
+#[derive(Clone, Copy, Debug)]
+pub struct Native;
+
+/// The pure Rust kernels, available on every target.
+#[derive(Clone, Copy, Debug)]
+pub struct Portable;
+
+// Both backends wrap kernels with the same raw signatures, `$kernels` is the module
+// they live in.
+macro_rules! impl_limb_backend {
+    ($backend:ty, $kernels:ident) => {
+        impl LimbBackend for $backend {
+            fn add(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]) {
+                let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+                assert!(dest.len() > a.len());
+                unsafe {
+                    $kernels::add_two_slices(
+                        a.as_ptr(),
+                        b.as_ptr(),
+                        dest.as_mut_ptr(),
+                        a.len() as IntLimb,
+                        b.len() as IntLimb,
+                    )
+                }
+            }
+
+            fn add_assign(a: &mut [IntLimb], b: &[IntLimb]) {
+                assert!(a.len() > b.len());
+                // The kernels read a limb of each operand before writing the same limb
+                // of dest, so dest may alias an operand. Both pointers come from the one
+                // mutable borrow, a shared one taken before it would be invalidated.
+                let dest = a.as_mut_ptr();
+                unsafe {
+                    $kernels::add_two_slices(
+                        dest,
+                        b.as_ptr(),
+                        dest,
+                        (a.len() - 1) as IntLimb,
+                        b.len() as IntLimb,
+                    )
+                }
+            }
+
+            fn sub(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]) {
+                assert!(a.len() >= b.len() && dest.len() >= a.len());
+                unsafe {
+                    $kernels::sub_two_slices(
+                        a.as_ptr(),
+                        b.as_ptr(),
+                        dest.as_mut_ptr(),
+                        a.len() as IntLimb,
+                        b.len() as IntLimb,
+                    )
+                }
+            }
+
+            fn sub_assign(a: &mut [IntLimb], b: &[IntLimb]) {
+                assert!(a.len() >= b.len());
+                let dest = a.as_mut_ptr();
+                unsafe {
+                    $kernels::sub_two_slices(
+                        dest,
+                        b.as_ptr(),
+                        dest,
+                        a.len() as IntLimb,
+                        b.len() as IntLimb,
+                    )
+                }
+            }
+
+            fn sub_from(a: &[IntLimb], b: &mut [IntLimb]) {
+                assert!(a.len() == b.len());
+                let (n, dest) = (b.len(), b.as_mut_ptr());
+                unsafe {
+                    $kernels::sub_two_slices(
+                        a.as_ptr(),
+                        dest,
+                        dest,
+                        a.len() as IntLimb,
+                        n as IntLimb,
+                    )
+                }
+            }
+
+            fn mul(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]) {
+                let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+                assert!(dest.len() >= a.len() + b.len());
+                unsafe {
+                    $kernels::mul_two_slices(
+                        a.as_ptr(),
+                        b.as_ptr(),
+                        dest.as_mut_ptr(),
+                        a.len() as IntLimb,
+                        b.len() as IntLimb,
+                    )
+                }
+            }
+
+            fn sqr(a: &[IntLimb], dest: &mut [IntLimb]) {
+                assert!(dest.len() >= 2 * a.len());
+                unsafe { $kernels::sqr_slice(a.as_ptr(), dest.as_mut_ptr(), a.len() as IntLimb) }
+            }
+
+            fn addmul_1(a: &[IntLimb], dest: &mut [IntLimb], c: IntLimb) -> IntLimb {
+                assert!(dest.len() >= a.len());
+                unsafe { $kernels::addmul_1(a.as_ptr(), dest.as_mut_ptr(), a.len() as IntLimb, c) }
+            }
+
+            fn submul_1(a: &[IntLimb], dest: &mut [IntLimb], c: IntLimb) -> IntLimb {
+                assert!(dest.len() >= a.len());
+                unsafe { $kernels::submul_1(a.as_ptr(), dest.as_mut_ptr(), a.len() as IntLimb, c) }
+            }
+
+            fn add_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb {
+                // The kernel propagates the carry without a bound, it stays in `a` when
+                // the top limb can take it.
+                let n = a.len();
+                if n > 1 && a[n - 1] != IntLimb::MAX {
+                    unsafe { $kernels::add_const(a.as_mut_ptr(), c) };
+                    0
+                } else {
+                    bounded_add_limb(a, c)
+                }
+            }
+
+            fn sub_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb {
+                let n = a.len();
+                if n > 1 && a[n - 1] != 0 {
+                    unsafe { $kernels::sub_const(a.as_mut_ptr(), c) };
+                    0
+                } else {
+                    bounded_sub_limb(a, c)
+                }
+            }
+
+            fn mul_limb(a: &mut [IntLimb], c: IntLimb) {
+                let n = a.len() - 1;
+                // The kernel writes the carry only when it is not zero.
+                a[n] = 0;
+                unsafe { $kernels::mul_const(a.as_mut_ptr(), c, n as IntLimb) }
+            }
+
+            fn div_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb {
+                assert!(c != 0);
+                unsafe { $kernels::div_const(a.as_mut_ptr(), c, a.len() as IntLimb) }
+            }
+
+            fn shl(a: &[IntLimb], dest: &mut [IntLimb], shift: u32) -> IntLimb {
+                assert!(dest.len() >= a.len() && shift < IntLimb::BITS);
+                unsafe {
+                    $kernels::shl_slice(
+                        a.as_ptr(),
+                        dest.as_mut_ptr(),
+                        a.len() as IntLimb,
+                        shift as IntLimb,
+                    )
+                }
+            }
+
+            fn shr(a: &[IntLimb], dest: &mut [IntLimb], shift: u32) -> IntLimb {
+                assert!(dest.len() >= a.len() && shift < IntLimb::BITS);
+                unsafe {
+                    $kernels::shr_slice(
+                        a.as_ptr(),
+                        dest.as_mut_ptr(),
+                        a.len() as IntLimb,
+                        shift as IntLimb,
+                    )
+                }
+            }
+
+            fn cmp(a: &[IntLimb], b: &[IntLimb]) -> Ordering {
+                assert!(a.len() == b.len());
+                let cmp_res =
+                    unsafe { $kernels::cmp_slices(a.as_ptr(), b.as_ptr(), a.len() as IntLimb) };
+                cmp_res.cmp(&0)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "verify-kernels"))]
+impl_limb_backend!(Native, asm_ops);
+impl_limb_backend!(Portable, portable);
+
+#[cfg(feature = "verify-kernels")]
+mod verify;
+
+// Carry propagation that stops at the end of `a`, for when the kernels could run past it.
+fn bounded_add_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb {
+    let mut carry = c;
+    for x in a.iter_mut() {
+        let (v, overflow) = x.overflowing_add(carry);
+        *x = v;
+        if !overflow {
+            return 0;
+        }
+        carry = 1;
+    }
+    carry
+}
+
+fn bounded_sub_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb {
+    let mut borrow = c;
+    for x in a.iter_mut() {
+        let (v, overflow) = x.overflowing_sub(borrow);
+        *x = v;
+        if !overflow {
+            return 0;
+        }
+        borrow = 1;
+    }
+    borrow
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_limb_cases<B: LimbBackend>() {
+        let x = IntLimb::MAX;
+
+        let mut a = [x, x, 1];
+        assert_eq!(0, B::add_limb(&mut a, 1));
+        assert_eq!([0, 0, 2], a);
+
+        let mut a = [x, x];
+        assert_eq!(1, B::add_limb(&mut a, 2));
+        assert_eq!([1, 0], a);
+
+        let mut a = [x - 1];
+        assert_eq!(1, B::add_limb(&mut a, 3));
+        assert_eq!([1], a);
+    }
+
+    fn sub_limb_cases<B: LimbBackend>() {
+        let x = IntLimb::MAX;
+
+        let mut a = [0, 0, 1];
+        assert_eq!(0, B::sub_limb(&mut a, 1));
+        assert_eq!([x, x, 0], a);
+
+        let mut a = [1, 0];
+        assert_eq!(1, B::sub_limb(&mut a, 2));
+        assert_eq!([x, x], a);
+
+        let mut a = [3];
+        assert_eq!(1, B::sub_limb(&mut a, 4));
+        assert_eq!([x], a);
+    }
+
+    fn in_place_cases<B: LimbBackend>() {
+        let x = IntLimb::MAX;
+
+        let mut a = [x, x, 7];
+        B::add_assign(&mut a, &[1]);
+        assert_eq!([0, 0, 1], a);
+
+        let mut a = [0, 0, 1];
+        B::sub_assign(&mut a, &[1]);
+        assert_eq!([x, x, 0], a);
+
+        let mut b = [1, 2];
+        B::sub_from(&[5, 7], &mut b);
+        assert_eq!([4, 5], b);
+
+        let mut a = [x, x, 9];
+        B::mul_limb(&mut a, 2);
+        assert_eq!([x - 1, x, 1], a);
+
+        let mut a = [0, 1, 3];
+        B::mul_limb(&mut a, 5);
+        assert_eq!([0, 5, 0], a);
+    }
+
+    #[test]
+    fn native_limb_ops() {
+        add_limb_cases::<Native>();
+        sub_limb_cases::<Native>();
+        in_place_cases::<Native>();
+    }
+
+    #[test]
+    fn portable_limb_ops() {
+        add_limb_cases::<Portable>();
+        sub_limb_cases::<Portable>();
+        in_place_cases::<Portable>();
+    }
+
+    #[test]
+    fn cmp_orders_from_the_top() {
+        assert_eq!(Ordering::Less, Native::cmp(&[5, 1], &[0, 2]));
+        assert_eq!(Ordering::Greater, Portable::cmp(&[5, 2], &[0, 2]));
+        assert_eq!(Ordering::Equal, Native::cmp(&[], &[]));
+    }
+}
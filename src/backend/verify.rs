@@ -0,0 +1,394 @@
+// With the `verify-kernels` feature, Native runs every call through the assembly kernels
+// and again through plain slice loops, and panics with the operands when they disagree.
+// The references don't share the wrappers with the kernels, so those are checked too.
+
+use super::*;
+use std::fmt::Debug;
+
+struct Asm;
+
+impl_limb_backend!(Asm, asm_ops);
+
+// `dest` is every slice the kernel writes to, the reference runs on a copy of it.
+fn verify<R, A, P>(
+    op: &str,
+    operands: &[&[IntLimb]],
+    dest: &mut [IntLimb],
+    asm: A,
+    reference: P,
+) -> R
+where
+    R: PartialEq + Debug,
+    A: FnOnce(&mut [IntLimb]) -> R,
+    P: FnOnce(&mut [IntLimb]) -> R,
+{
+    let before = dest.to_vec();
+    let mut expected_dest = before.clone();
+    let expected = reference(&mut expected_dest);
+    let actual = asm(dest);
+
+    if actual != expected || *dest != *expected_dest {
+        panic!(
+            "{} kernel mismatch\n operands: {:?}\n dest before: {:?}\n dest: {:?}, expected {:?}\n result: {:?}, expected {:?}",
+            op, operands, before, dest, expected_dest, actual, expected
+        );
+    }
+    actual
+}
+
+impl LimbBackend for Native {
+    fn add(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]) {
+        verify(
+            "add",
+            &[a, b],
+            dest,
+            |d| Asm::add(a, b, d),
+            |d| reference::add(a, b, d),
+        )
+    }
+
+    fn add_assign(a: &mut [IntLimb], b: &[IntLimb]) {
+        verify(
+            "add_assign",
+            &[b],
+            a,
+            |a| Asm::add_assign(a, b),
+            |a| reference::add_assign(a, b),
+        )
+    }
+
+    fn sub(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]) {
+        verify(
+            "sub",
+            &[a, b],
+            dest,
+            |d| Asm::sub(a, b, d),
+            |d| reference::sub(a, b, d),
+        )
+    }
+
+    fn sub_assign(a: &mut [IntLimb], b: &[IntLimb]) {
+        verify(
+            "sub_assign",
+            &[b],
+            a,
+            |a| Asm::sub_assign(a, b),
+            |a| reference::sub_assign(a, b),
+        )
+    }
+
+    fn sub_from(a: &[IntLimb], b: &mut [IntLimb]) {
+        verify(
+            "sub_from",
+            &[a],
+            b,
+            |b| Asm::sub_from(a, b),
+            |b| reference::sub_from(a, b),
+        )
+    }
+
+    fn mul(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]) {
+        verify(
+            "mul",
+            &[a, b],
+            dest,
+            |d| Asm::mul(a, b, d),
+            |d| reference::mul(a, b, d),
+        )
+    }
+
+    fn sqr(a: &[IntLimb], dest: &mut [IntLimb]) {
+        verify(
+            "sqr",
+            &[a],
+            dest,
+            |d| Asm::sqr(a, d),
+            |d| reference::sqr(a, d),
+        )
+    }
+
+    fn addmul_1(a: &[IntLimb], dest: &mut [IntLimb], c: IntLimb) -> IntLimb {
+        verify(
+            "addmul_1",
+            &[a, &[c]],
+            dest,
+            |d| Asm::addmul_1(a, d, c),
+            |d| reference::addmul_1(a, d, c),
+        )
+    }
+
+    fn submul_1(a: &[IntLimb], dest: &mut [IntLimb], c: IntLimb) -> IntLimb {
+        verify(
+            "submul_1",
+            &[a, &[c]],
+            dest,
+            |d| Asm::submul_1(a, d, c),
+            |d| reference::submul_1(a, d, c),
+        )
+    }
+
+    fn add_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb {
+        verify(
+            "add_limb",
+            &[&[c]],
+            a,
+            |a| Asm::add_limb(a, c),
+            |a| reference::add_limb(a, c),
+        )
+    }
+
+    fn sub_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb {
+        verify(
+            "sub_limb",
+            &[&[c]],
+            a,
+            |a| Asm::sub_limb(a, c),
+            |a| reference::sub_limb(a, c),
+        )
+    }
+
+    fn mul_limb(a: &mut [IntLimb], c: IntLimb) {
+        verify(
+            "mul_limb",
+            &[&[c]],
+            a,
+            |a| Asm::mul_limb(a, c),
+            |a| reference::mul_limb(a, c),
+        )
+    }
+
+    fn div_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb {
+        verify(
+            "div_limb",
+            &[&[c]],
+            a,
+            |a| Asm::div_limb(a, c),
+            |a| reference::div_limb(a, c),
+        )
+    }
+
+    fn shl(a: &[IntLimb], dest: &mut [IntLimb], shift: u32) -> IntLimb {
+        verify(
+            "shl",
+            &[a, &[shift as IntLimb]],
+            dest,
+            |d| Asm::shl(a, d, shift),
+            |d| reference::shl(a, d, shift),
+        )
+    }
+
+    fn shr(a: &[IntLimb], dest: &mut [IntLimb], shift: u32) -> IntLimb {
+        verify(
+            "shr",
+            &[a, &[shift as IntLimb]],
+            dest,
+            |d| Asm::shr(a, d, shift),
+            |d| reference::shr(a, d, shift),
+        )
+    }
+
+    fn cmp(a: &[IntLimb], b: &[IntLimb]) -> Ordering {
+        verify(
+            "cmp",
+            &[a, b],
+            &mut [],
+            |_| Asm::cmp(a, b),
+            |_| reference::cmp(a, b),
+        )
+    }
+}
+
+// The `LimbBackend` semantics written out limb by limb.
+#[allow(clippy::needless_range_loop)]
+mod reference {
+    use crate::IntLimb;
+    use std::cmp::Ordering;
+
+    const BITS: u32 = IntLimb::BITS;
+
+    fn add_carry(x: IntLimb, y: IntLimb, carry: IntLimb) -> (IntLimb, IntLimb) {
+        let s = x as u128 + y as u128 + carry as u128;
+        (s as IntLimb, (s >> BITS) as IntLimb)
+    }
+
+    fn sub_borrow(x: IntLimb, y: IntLimb, borrow: IntLimb) -> (IntLimb, IntLimb) {
+        let (d, b1) = x.overflowing_sub(y);
+        let (d, b2) = d.overflowing_sub(borrow);
+        (d, (b1 || b2) as IntLimb)
+    }
+
+    pub(super) fn add(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]) {
+        let n = usize::max(a.len(), b.len());
+        let mut carry = 0;
+        for i in 0..n {
+            let x = a.get(i).copied().unwrap_or(0);
+            let y = b.get(i).copied().unwrap_or(0);
+            (dest[i], carry) = add_carry(x, y, carry);
+        }
+        dest[n] = carry;
+    }
+
+    pub(super) fn add_assign(a: &mut [IntLimb], b: &[IntLimb]) {
+        let n = a.len() - 1;
+        let mut carry = 0;
+        for i in 0..n {
+            (a[i], carry) = add_carry(a[i], b.get(i).copied().unwrap_or(0), carry);
+        }
+        a[n] = carry;
+    }
+
+    pub(super) fn sub(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]) {
+        let mut borrow = 0;
+        for i in 0..a.len() {
+            (dest[i], borrow) = sub_borrow(a[i], b.get(i).copied().unwrap_or(0), borrow);
+        }
+    }
+
+    pub(super) fn sub_assign(a: &mut [IntLimb], b: &[IntLimb]) {
+        let mut borrow = 0;
+        for i in 0..a.len() {
+            (a[i], borrow) = sub_borrow(a[i], b.get(i).copied().unwrap_or(0), borrow);
+        }
+    }
+
+    pub(super) fn sub_from(a: &[IntLimb], b: &mut [IntLimb]) {
+        let mut borrow = 0;
+        for i in 0..a.len() {
+            (b[i], borrow) = sub_borrow(a[i], b[i], borrow);
+        }
+    }
+
+    pub(super) fn addmul_1(a: &[IntLimb], dest: &mut [IntLimb], c: IntLimb) -> IntLimb {
+        let mut carry = 0;
+        for i in 0..a.len() {
+            let t = a[i] as u128 * c as u128 + dest[i] as u128 + carry as u128;
+            dest[i] = t as IntLimb;
+            carry = (t >> BITS) as IntLimb;
+        }
+        carry
+    }
+
+    pub(super) fn submul_1(a: &[IntLimb], dest: &mut [IntLimb], c: IntLimb) -> IntLimb {
+        let mut borrow = 0;
+        for i in 0..a.len() {
+            let t = a[i] as u128 * c as u128 + borrow as u128;
+            let (d, b) = sub_borrow(dest[i], t as IntLimb, 0);
+            dest[i] = d;
+            borrow = (t >> BITS) as IntLimb + b;
+        }
+        borrow
+    }
+
+    pub(super) fn mul(a: &[IntLimb], b: &[IntLimb], dest: &mut [IntLimb]) {
+        for i in 0..b.len() {
+            dest[i + a.len()] = addmul_1(a, &mut dest[i..], b[i]);
+        }
+    }
+
+    pub(super) fn sqr(a: &[IntLimb], dest: &mut [IntLimb]) {
+        dest[..2 * a.len()].fill(0);
+        mul(a, a, dest);
+    }
+
+    pub(super) fn add_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb {
+        let mut carry = c;
+        for x in a.iter_mut() {
+            (*x, carry) = add_carry(*x, carry, 0);
+        }
+        carry
+    }
+
+    pub(super) fn sub_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb {
+        let mut borrow = c;
+        for x in a.iter_mut() {
+            (*x, borrow) = sub_borrow(*x, borrow, 0);
+        }
+        borrow
+    }
+
+    pub(super) fn mul_limb(a: &mut [IntLimb], c: IntLimb) {
+        let n = a.len() - 1;
+        let mut carry = 0;
+        for x in a[..n].iter_mut() {
+            let t = *x as u128 * c as u128 + carry as u128;
+            *x = t as IntLimb;
+            carry = (t >> BITS) as IntLimb;
+        }
+        a[n] = carry;
+    }
+
+    pub(super) fn div_limb(a: &mut [IntLimb], c: IntLimb) -> IntLimb {
+        let mut rem = 0;
+        for x in a.iter_mut() {
+            let t = ((rem as u128) << BITS) | *x as u128;
+            *x = (t / c as u128) as IntLimb;
+            rem = (t % c as u128) as IntLimb;
+        }
+        rem
+    }
+
+    pub(super) fn shl(a: &[IntLimb], dest: &mut [IntLimb], shift: u32) -> IntLimb {
+        let mut low = 0;
+        for i in 0..a.len() {
+            let wide = ((a[i] as u128) << BITS | low as u128) << shift;
+            dest[i] = (wide >> BITS) as IntLimb;
+            low = a[i];
+        }
+        ((low as u128) << shift >> BITS) as IntLimb
+    }
+
+    pub(super) fn shr(a: &[IntLimb], dest: &mut [IntLimb], shift: u32) -> IntLimb {
+        let mut high = 0;
+        for i in (0..a.len()).rev() {
+            let wide = ((high as u128) << BITS | a[i] as u128) >> shift;
+            dest[i] = wide as IntLimb;
+            high = a[i];
+        }
+        ((high as u128) << BITS >> shift) as IntLimb
+    }
+
+    pub(super) fn cmp(a: &[IntLimb], b: &[IntLimb]) -> Ordering {
+        a.iter().rev().cmp(b.iter().rev())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "add kernel mismatch")]
+    fn mismatch_panics() {
+        verify("add", &[&[1]], &mut [0], |d| d[0] = 1, |d| d[0] = 2);
+    }
+
+    #[test]
+    fn matching_results_pass_through() {
+        let mut dest = [0; 3];
+        Native::add(&[IntLimb::MAX, 1], &[1], &mut dest);
+        assert_eq!([0, 2, 0], dest);
+        assert_eq!(7, verify("cmp", &[], &mut [], |_| 7, |_| 7));
+    }
+
+    #[test]
+    fn wrappers_match_references() {
+        let x = IntLimb::MAX;
+
+        // Top limb fast path of add_limb and sub_limb, and mul_limb's stale carry limb.
+        let mut a = [x, 5];
+        assert_eq!(0, Native::add_limb(&mut a, 1));
+        assert_eq!([0, 6], a);
+        assert_eq!(0, Native::sub_limb(&mut a, 1));
+        assert_eq!([x, 5], a);
+
+        let mut a = [3, 99];
+        Native::mul_limb(&mut a, 2);
+        assert_eq!([6, 0], a);
+
+        let mut dest = [0; 2];
+        assert_eq!(1, Native::shl(&[x, x], &mut dest, 1));
+        assert_eq!([x << 1, x], dest);
+        assert_eq!(1 << (IntLimb::BITS - 1), Native::shr(&[1, 2], &mut dest, 1));
+        assert_eq!([0, 1], dest);
+    }
+}
@@ -1,14 +1,33 @@
-use crate::algorithms::karatsuba;
-use crate::asm_ops::wrapped_ops;
-use crate::asm_ops::{add_const, cmp_slices, div_const, mul_const, sub_const, sub_two_slices};
+use std::cmp::Ordering;
+
+use crate::algorithms::{
+    burnikel_ziegler, karatsuba, karatsuba_scratch_size, karatsuba_sqr, karatsuba_sqr_scratch_size,
+    newton_div, ntt_mul, toom3, toom3_sqr, toom4, toom4_sqr,
+};
+use crate::backend::LimbBackend;
+use crate::bit_ops::shr;
 use crate::errors::ArithmeticError;
-use crate::utils::{bit_len, trim_zeros};
+use crate::utils::{bit_len, cmp_repr, trim_zeros};
+use crate::workspace::Workspace;
 use crate::IntLimb;
 
+const LIMB_BITS: usize = IntLimb::BITS as usize;
 const KARATSUBA_THRESHOLD: usize = 13;
+const TOOM3_THRESHOLD: usize = 220;
+const TOOM4_THRESHOLD: usize = 1000;
+const NTT_THRESHOLD: usize = 14000;
+
+// The squaring kernel is cheap enough that the recursive algorithms pay off much later.
+const KARATSUBA_SQR_THRESHOLD: usize = 96;
+const TOOM3_SQR_THRESHOLD: usize = 2500;
+const TOOM4_SQR_THRESHOLD: usize = 4000;
+const NTT_SQR_THRESHOLD: usize = 6000;
+
+const BURNIKEL_ZIEGLER_THRESHOLD: usize = 60;
+const NEWTON_DIV_THRESHOLD: usize = 16000;
 
 #[inline]
-fn mul_helper(left: &[IntLimb], right: &[IntLimb]) -> Vec<IntLimb> {
+fn mul_helper<B: LimbBackend>(left: &[IntLimb], right: &[IntLimb]) -> Vec<IntLimb> {
     let (m, n) = (left.len(), right.len());
     if m == 1 && left[0] == 1 {
         return right.to_vec();
@@ -24,7 +43,7 @@ fn mul_helper(left: &[IntLimb], right: &[IntLimb]) -> Vec<IntLimb> {
 
     let mut repr = vec![0; m + n];
 
-    wrapped_ops::unsafe_mul_two_slices(&left, &right, &mut repr);
+    B::mul(left, right, &mut repr);
 
     while let Some(v) = repr.last() {
         if *v == 0 {
@@ -36,7 +55,7 @@ fn mul_helper(left: &[IntLimb], right: &[IntLimb]) -> Vec<IntLimb> {
     repr
 }
 
-pub(crate) fn add(left: &[IntLimb], right: &[IntLimb]) -> Vec<IntLimb> {
+pub(crate) fn add<B: LimbBackend>(left: &[IntLimb], right: &[IntLimb]) -> Vec<IntLimb> {
     let mut dst = Vec::new();
     let l: &[IntLimb];
     let r: &[IntLimb];
@@ -52,13 +71,13 @@ pub(crate) fn add(left: &[IntLimb], right: &[IntLimb]) -> Vec<IntLimb> {
     let size = usize::max(l.len(), r.len());
     dst.resize(size + 1, 0);
 
-    wrapped_ops::unsafe_add_two_slices(&l, &r, &mut dst);
+    B::add(l, r, &mut dst);
     trim_zeros(&mut dst);
     dst
 }
 
 #[allow(clippy::comparison_chain)]
-pub(crate) fn sub(left: &[IntLimb], right: &[IntLimb]) -> (i8, Vec<IntLimb>) {
+pub(crate) fn sub<B: LimbBackend>(left: &[IntLimb], right: &[IntLimb]) -> (i8, Vec<IntLimb>) {
     let mut dst = Vec::new();
     let mut sign = 0;
     let mut l = left;
@@ -95,7 +114,7 @@ pub(crate) fn sub(left: &[IntLimb], right: &[IntLimb]) -> (i8, Vec<IntLimb>) {
     let size = usize::max(l.len(), r.len());
     dst.resize(size, 0);
 
-    wrapped_ops::unsafe_sub_two_slices(&l, &r, &mut dst);
+    B::sub(l, r, &mut dst);
     trim_zeros(&mut dst);
 
     if dst.is_empty() {
@@ -105,22 +124,286 @@ pub(crate) fn sub(left: &[IntLimb], right: &[IntLimb]) -> (i8, Vec<IntLimb>) {
     (sign, dst)
 }
 
+pub(crate) fn add_limb<B: LimbBackend>(left: &[IntLimb], c: IntLimb) -> Vec<IntLimb> {
+    let mut dst = Vec::with_capacity(left.len() + 1);
+    dst.extend_from_slice(left);
+    dst.push(0);
+
+    // The extra zero limb takes the carry.
+    B::add_limb(&mut dst, c);
+    trim_zeros(&mut dst);
+    dst
+}
+
+pub(crate) fn sub_limb<B: LimbBackend>(left: &[IntLimb], c: IntLimb) -> (i8, Vec<IntLimb>) {
+    if left.len() > 1 || (left.len() == 1 && left[0] >= c) {
+        let mut dst = left.to_vec();
+
+        B::sub_limb(&mut dst, c);
+        trim_zeros(&mut dst);
+
+        let sign = if dst.is_empty() { 0 } else { 1 };
+        return (sign, dst);
+    }
+
+    let l = left.first().copied().unwrap_or(0);
+    if l == c {
+        (0, Vec::new())
+    } else {
+        (-1, Vec::from([c - l]))
+    }
+}
+
+pub(crate) fn mul_limb<B: LimbBackend>(left: &[IntLimb], c: IntLimb) -> Vec<IntLimb> {
+    if c == 0 {
+        return Vec::new();
+    }
+
+    let mut dst = Vec::with_capacity(left.len() + 1);
+    dst.extend_from_slice(left);
+    dst.push(0);
+
+    B::mul_limb(&mut dst, c);
+    trim_zeros(&mut dst);
+    dst
+}
+
+// Adds `right` into `left`, reusing its allocation and growing it only by the limbs
+// the result needs.
+pub(crate) fn add_assign<B: LimbBackend>(left: &mut Vec<IntLimb>, right: &[IntLimb]) {
+    let (n, m) = (left.len(), right.len());
+    if m == 0 {
+        return;
+    }
+
+    left.resize(usize::max(n, m) + 1, 0);
+
+    // Zero-extended to the longer operand, the extra top limb takes the carry.
+    B::add_assign(left, right);
+
+    trim_zeros(left);
+}
+
+// Replaces `left` with `|left - right|` in place and returns the sign of `left - right`.
+pub(crate) fn sub_assign<B: LimbBackend>(left: &mut Vec<IntLimb>, right: &[IntLimb]) -> i8 {
+    let m = right.len();
+
+    let sign = match cmp_repr::<B>(left, right) {
+        Ordering::Equal => {
+            left.clear();
+            return 0;
+        }
+        Ordering::Greater => {
+            B::sub_assign(left, right);
+            1
+        }
+        Ordering::Less => {
+            left.resize(m, 0);
+            B::sub_from(right, left);
+            -1
+        }
+    };
+
+    trim_zeros(left);
+    sign
+}
+
+pub(crate) fn mul_assign<B: LimbBackend>(left: &mut Vec<IntLimb>, right: &[IntLimb]) {
+    if left.is_empty() || right.is_empty() {
+        left.clear();
+    } else if right.len() == 1 {
+        left.push(0);
+        B::mul_limb(left, right[0]);
+        trim_zeros(left);
+    } else if left.len() == 1 {
+        *left = mul_limb::<B>(right, left[0]);
+    } else {
+        *left = mul::<B>(left, right);
+    }
+}
+
+// Replaces `left` with the quotient of `left / right`, reusing its allocation, and returns
+// the remainder. `left` is left untouched on error.
+pub(crate) fn div_assign<B: LimbBackend>(
+    left: &mut Vec<IntLimb>,
+    right: &[IntLimb],
+) -> Result<Vec<IntLimb>, ArithmeticError> {
+    if right.is_empty() {
+        return Err(ArithmeticError::DividedByZero);
+    }
+
+    if right.len() == 1 && right[0] == 1 {
+        Ok(Vec::new())
+    } else if right.len() == 1 {
+        left.reverse();
+        let remainder = B::div_limb(left, right[0]);
+        left.reverse();
+        trim_zeros(left);
+
+        Ok(if remainder == 0 {
+            Vec::new()
+        } else {
+            Vec::from([remainder])
+        })
+    } else if right.len() > left.len() {
+        Ok(std::mem::take(left))
+    } else {
+        let (q, r) = div_large::<B>(left, right, &mut Workspace::new());
+        left.clear();
+        left.extend_from_slice(&q);
+
+        Ok(r)
+    }
+}
+
+// Replaces `left` with the remainder of `left / right`, reusing its allocation.
+pub(crate) fn rem_assign<B: LimbBackend>(
+    left: &mut Vec<IntLimb>,
+    right: &[IntLimb],
+) -> Result<(), ArithmeticError> {
+    let remainder = div_assign::<B>(left, right)?;
+    left.clear();
+    left.extend_from_slice(&remainder);
+
+    Ok(())
+}
+
 #[inline]
-pub(crate) fn base_mul(left: &[IntLimb], right: &[IntLimb]) -> Vec<IntLimb> {
+pub(crate) fn base_mul<B: LimbBackend>(left: &[IntLimb], right: &[IntLimb]) -> Vec<IntLimb> {
     if left.len() > right.len() {
-        mul_helper(left, right)
+        mul_helper::<B>(left, right)
+    } else {
+        mul_helper::<B>(right, left)
+    }
+}
+
+pub(crate) fn base_sqr<B: LimbBackend>(x: &[IntLimb]) -> Vec<IntLimb> {
+    let mut repr = vec![0; 2 * x.len()];
+
+    B::sqr(x, &mut repr);
+    trim_zeros(&mut repr);
+    repr
+}
+
+pub(crate) fn sqr<B: LimbBackend>(x: &[IntLimb]) -> Vec<IntLimb> {
+    sqr_with::<B>(x, &mut Workspace::new())
+}
+
+pub(crate) fn sqr_with<B: LimbBackend>(x: &[IntLimb], workspace: &mut Workspace) -> Vec<IntLimb> {
+    let n = x.len();
+
+    if n >= NTT_SQR_THRESHOLD {
+        ntt_mul(x, x)
+    } else if n >= TOOM4_SQR_THRESHOLD {
+        toom4_sqr::<B>(x, sqr_with::<B>, workspace)
+    } else if n >= TOOM3_SQR_THRESHOLD {
+        toom3_sqr::<B>(x, sqr_with::<B>, workspace)
+    } else if n > KARATSUBA_SQR_THRESHOLD {
+        let mut dest = vec![0; 2 * n];
+        let scratch = workspace.scratch(karatsuba_sqr_scratch_size(n, KARATSUBA_SQR_THRESHOLD));
+
+        karatsuba_sqr::<B>(x, &mut dest, KARATSUBA_SQR_THRESHOLD, scratch);
+        trim_zeros(&mut dest);
+        dest
+    } else {
+        base_sqr::<B>(x)
+    }
+}
+
+// Scratch `mul_with` takes from its workspace, the Karatsuba products at the bottom of
+// the Toom recursion. Toom multiplies parts of l limbs that the evaluation grows by one,
+// or slices as long as the shorter operand when that would leave its top part empty.
+pub(crate) fn mul_scratch_size(n: usize, m: usize) -> usize {
+    let short = usize::min(n, m);
+    let parts = if short >= NTT_THRESHOLD {
+        return 0;
+    } else if short >= TOOM4_THRESHOLD {
+        4
+    } else if short >= TOOM3_THRESHOLD {
+        3
+    } else {
+        return karatsuba_scratch_size(n, m, KARATSUBA_THRESHOLD);
+    };
+
+    let l = usize::max(n, m).div_ceil(parts);
+    if short <= (parts - 1) * l {
+        mul_scratch_size(short, short)
+    } else {
+        mul_scratch_size(l + 1, l + 1)
+    }
+}
+
+// Scratch `sqr_with` takes from its workspace, like `mul_scratch_size` for the squaring
+// thresholds.
+pub(crate) fn sqr_scratch_size(n: usize) -> usize {
+    let parts = if n >= NTT_SQR_THRESHOLD {
+        return 0;
+    } else if n >= TOOM4_SQR_THRESHOLD {
+        4
+    } else if n >= TOOM3_SQR_THRESHOLD {
+        3
+    } else {
+        return karatsuba_sqr_scratch_size(n, KARATSUBA_SQR_THRESHOLD);
+    };
+
+    sqr_scratch_size(n.div_ceil(parts) + 1)
+}
+
+// Scratch `div_with` takes from its workspace: the normalized operands of the schoolbook
+// division, or the products of the recursive algorithms.
+pub(crate) fn div_scratch_size(n: usize, m: usize) -> usize {
+    if m > n {
+        return 0;
+    }
+
+    usize::max(n + m + 1, mul_scratch_size(m, m))
+}
+
+pub(crate) fn mul<B: LimbBackend>(left: &[IntLimb], right: &[IntLimb]) -> Vec<IntLimb> {
+    mul_with::<B>(left, right, &mut Workspace::new())
+}
+
+pub(crate) fn mul_with<B: LimbBackend>(
+    left: &[IntLimb],
+    right: &[IntLimb],
+    workspace: &mut Workspace,
+) -> Vec<IntLimb> {
+    if std::ptr::eq(left, right) {
+        return sqr_with::<B>(left, workspace);
+    }
+
+    let n = usize::min(left.len(), right.len());
+
+    if n >= NTT_THRESHOLD {
+        ntt_mul(left, right)
+    } else if n >= TOOM4_THRESHOLD {
+        toom4::<B>(left, right, mul_with::<B>, workspace)
+    } else if n >= TOOM3_THRESHOLD {
+        toom3::<B>(left, right, mul_with::<B>, workspace)
+    } else if n > KARATSUBA_THRESHOLD {
+        let (n, m) = (left.len(), right.len());
+        let mut dest = vec![0; n + m];
+        let scratch = workspace.scratch(karatsuba_scratch_size(n, m, KARATSUBA_THRESHOLD));
+
+        karatsuba::<B>(left, right, &mut dest, KARATSUBA_THRESHOLD, scratch);
+        trim_zeros(&mut dest);
+        dest
     } else {
-        mul_helper(right, left)
+        base_mul::<B>(left, right)
     }
 }
 
-pub(crate) fn mul(left: &[IntLimb], right: &[IntLimb]) -> Vec<IntLimb> {
-    karatsuba(left, right, KARATSUBA_THRESHOLD, base_mul)
+pub(crate) fn div<B: LimbBackend>(
+    left: &[IntLimb],
+    right: &[IntLimb],
+) -> Result<(Vec<IntLimb>, Vec<IntLimb>), ArithmeticError> {
+    div_with::<B>(left, right, &mut Workspace::new())
 }
 
-pub(crate) fn div(
+pub(crate) fn div_with<B: LimbBackend>(
     left: &[IntLimb],
     right: &[IntLimb],
+    workspace: &mut Workspace,
 ) -> Result<(Vec<IntLimb>, Vec<IntLimb>), ArithmeticError> {
     if right.is_empty() {
         return Err(ArithmeticError::DividedByZero);
@@ -131,7 +414,7 @@ pub(crate) fn div(
     } else if right.len() == 1 {
         let mut l = left.to_vec();
         l.reverse();
-        let remainder = unsafe { div_const(l.as_mut_ptr(), right[0], l.len() as IntLimb) };
+        let remainder = B::div_limb(&mut l, right[0]);
         l.reverse();
 
         let remainder_repr = if remainder == 0 {
@@ -145,118 +428,110 @@ pub(crate) fn div(
         return Ok((Vec::from([]), left.to_vec()));
     }
 
-    // Knuth The art of Computer Programming vol2 3rd edition 4.3.1 Algorithm D
-    // Poorly written by me :(.
+    Ok(div_large::<B>(left, right, workspace))
+}
+
+// Chooses the division algorithm by the sizes of the divisor and the quotient, `right`
+// must have at least two limbs.
+fn div_large<B: LimbBackend>(
+    left: &[IntLimb],
+    right: &[IntLimb],
+    workspace: &mut Workspace,
+) -> (Vec<IntLimb>, Vec<IntLimb>) {
+    if right.len() > left.len() {
+        return (Vec::new(), left.to_vec());
+    }
+
+    let (n, size) = (
+        right.len(),
+        usize::min(right.len(), left.len() - right.len()),
+    );
+
+    // The reciprocal costs a few multiplications, so it only pays off when it is reused
+    // for several blocks of a long quotient. Measured against Burnikel-Ziegler: 16000
+    // limb divisor with an 8n quotient 880ms vs 1007ms but 474ms vs 420ms at 4n, and
+    // Newton loses for every quotient length with divisors of 4000 limbs or less.
+    if n >= NEWTON_DIV_THRESHOLD && left.len() - n >= 8 * n {
+        newton_div::<B>(left, right, NEWTON_DIV_THRESHOLD, div_large::<B>, workspace)
+    } else if size >= BURNIKEL_ZIEGLER_THRESHOLD {
+        burnikel_ziegler::<B>(
+            left,
+            right,
+            BURNIKEL_ZIEGLER_THRESHOLD,
+            schoolbook_div::<B>,
+            workspace,
+        )
+    } else {
+        schoolbook_div::<B>(left, right, workspace)
+    }
+}
+
+// Knuth The art of Computer Programming vol2 3rd edition 4.3.1 Algorithm D, `right` must
+// have at least two limbs.
+fn schoolbook_div<B: LimbBackend>(
+    left: &[IntLimb],
+    right: &[IntLimb],
+    workspace: &mut Workspace,
+) -> (Vec<IntLimb>, Vec<IntLimb>) {
+    if right.len() > left.len() {
+        return (Vec::new(), left.to_vec());
+    }
+
     let (m, n) = (left.len(), right.len());
-    let (mut u, mut v) = (left.to_vec(), right.to_vec());
-    u.push(0);
-    let mut q = vec![0; m - n + 1];
 
-    let mut r = vec![0; 2];
-    let mut br_u = vec![0; 2];
-    let mut qp = vec![0; 2];
-    let mut qp_copy = vec![0; 3];
-    let mut vq;
-
-    let d = IntLimb::pow(2, bit_len(IntLimb::MAX) - bit_len(v[n - 1]));
-
-    unsafe {
-        mul_const(u.as_mut_ptr(), d, (u.len() - 1) as IntLimb);
-        mul_const(v.as_mut_ptr(), d, v.len() as IntLimb);
-    }
-    let (vn_1, vn_2) = (v[n - 1], v[n - 2]);
-
-    for j in (0..(m - n + 1)).rev() {
-        qp[1] = u[j + n - 1];
-        qp[0] = u[j + n];
-
-        let remainder = unsafe { div_const(qp.as_mut_ptr(), vn_1, 2) };
-        qp.reverse();
-
-        r[0] = remainder;
-        r[1] = 0;
-
-        loop {
-            if qp == [0, 1] {
-                qp[0] = IntLimb::MAX;
-                qp[1] = 0;
-                unsafe { add_const(r.as_mut_ptr(), vn_1) }
-            } else {
-                qp_copy[0] = qp[0];
-                qp_copy[1] = qp[1];
-                qp_copy[2] = 0;
-
-                br_u[0] = u[j + n - 2];
-                br_u[1] = r[0];
-
-                unsafe { mul_const(qp_copy.as_mut_ptr(), vn_2, 2) };
-
-                if qp_copy[2] != 0 || unsafe { cmp_slices(qp_copy.as_ptr(), br_u.as_ptr(), 2) } == 1
-                {
-                    unsafe {
-                        sub_const(qp.as_mut_ptr(), 1);
-                        add_const(r.as_mut_ptr(), vn_1);
-                    }
-                } else {
-                    break;
-                }
-            }
+    // D1: normalize so the top bit of the divisor is set, u gets an extra top limb. Both
+    // live in the workspace.
+    let shift = LIMB_BITS as u32 - bit_len(right[n - 1]);
+    let (u, v) = workspace.scratch(m + 1 + n).split_at_mut(m + 1);
+    u[m] = B::shl(left, &mut u[..m], shift);
+    B::shl(right, v, shift);
+
+    let (vn_1, vn_2) = (v[n - 1] as u128, v[n - 2] as u128);
+    let mut q = vec![0; m - n + 1];
 
-            if r[1] != 0 {
+    for j in (0..=m - n).rev() {
+        // D3: estimate the quotient limb from the top limbs, it is at most one too large
+        // after the correction.
+        let top = ((u[j + n] as u128) << LIMB_BITS) | u[j + n - 1] as u128;
+        let (mut qhat, mut rhat) = (top / vn_1, top % vn_1);
+
+        while qhat > IntLimb::MAX as u128
+            || qhat * vn_2 > ((rhat << LIMB_BITS) | u[j + n - 2] as u128)
+        {
+            qhat -= 1;
+            rhat += vn_1;
+            if rhat > IntLimb::MAX as u128 {
                 break;
             }
         }
 
-        vq = v.to_vec();
-        vq.push(0);
-
-        if !qp.is_empty() {
-            unsafe {
-                mul_const(vq.as_mut_ptr(), qp[0], n as IntLimb);
-                if cmp_slices(
-                    vq.as_ptr(),
-                    u.as_ptr().offset(j as isize),
-                    (n + 1) as IntLimb,
-                ) == 1
-                {
-                    sub_two_slices(
-                        vq.as_ptr(),
-                        v.as_ptr(),
-                        vq.as_mut_ptr(),
-                        (n + 1) as IntLimb,
-                        (n + 1) as IntLimb,
-                    );
-                    qp[0] -= 1;
-                }
-                sub_two_slices(
-                    u.as_ptr().offset(j as isize),
-                    vq.as_ptr(),
-                    u.as_mut_ptr().offset(j as isize),
-                    (n + 1) as IntLimb,
-                    (n + 1) as IntLimb,
-                );
-                trim_zeros(&mut vq);
-            }
-            q[j] = qp[0];
-        } else {
-            q[j] = 0;
+        // D4: u[j..=j + n] -= qhat * v
+        let mut qhat = qhat as IntLimb;
+        let borrow = B::submul_1(v, &mut u[j..j + n], qhat);
+        let (top, negative) = u[j + n].overflowing_sub(borrow);
+        u[j + n] = top;
+
+        // D6: add back, the top limb wraps around to zero.
+        if negative {
+            qhat -= 1;
+            B::add_assign(&mut u[j..=j + n], v);
+            u[j + n] = 0;
         }
-    }
 
-    u.reverse();
-    unsafe {
-        div_const(u.as_mut_ptr().offset((m - n + 1) as isize), d, n as IntLimb);
+        q[j] = qhat;
     }
-    u.reverse();
+
+    // D8: unnormalize the remainder.
+    let (r, _) = shr::<B>(&u[..n], shift as usize);
 
     trim_zeros(&mut q);
-    trim_zeros(&mut u);
-    return Ok((q.to_vec(), u.to_vec()));
+    (q, r)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::Native;
 
     #[test]
     fn add_reversed_digits_even_sizes() {
@@ -265,7 +540,7 @@ mod tests {
 
         let c = Vec::from([10, 11, 4, 16, 18]);
 
-        assert_eq!(c, add(&a, &b));
+        assert_eq!(c, add::<Native>(&a, &b));
     }
 
     #[test]
@@ -275,7 +550,7 @@ mod tests {
 
         let c = Vec::from([10, 9, 9, 9, 9]);
 
-        assert_eq!(c, add(&a, &b));
+        assert_eq!(c, add::<Native>(&a, &b));
     }
 
     #[test]
@@ -285,7 +560,7 @@ mod tests {
 
         let c = (1, Vec::from([8, 7]));
 
-        assert_eq!(c, sub(&a, &b));
+        assert_eq!(c, sub::<Native>(&a, &b));
     }
 
     #[test]
@@ -296,7 +571,7 @@ mod tests {
 
         let c = (-1, Vec::from([x - 7, 8, 9, 9, 9]));
 
-        assert_eq!(c, sub(&a, &b));
+        assert_eq!(c, sub::<Native>(&a, &b));
     }
 
     #[test]
@@ -307,7 +582,119 @@ mod tests {
 
         let c = (-1, Vec::from([x - 7, 0, 7, 9, 9]));
 
-        assert_eq!(c, sub(&a, &b));
+        assert_eq!(c, sub::<Native>(&a, &b));
+    }
+
+    #[test]
+    fn add_limb_carries() {
+        let x = IntLimb::MAX;
+
+        assert_eq!(Vec::from([0, 0, 1]), add_limb::<Native>(&[x, x], 1));
+        assert_eq!(Vec::from([7]), add_limb::<Native>(&[], 7));
+    }
+
+    #[test]
+    fn sub_limb_signs() {
+        let x = IntLimb::MAX;
+
+        assert_eq!((1, Vec::from([x, x])), sub_limb::<Native>(&[0, 0, 1], 1));
+        assert_eq!((0, Vec::new()), sub_limb::<Native>(&[5], 5));
+        assert_eq!((-1, Vec::from([3])), sub_limb::<Native>(&[5], 8));
+        assert_eq!((-1, Vec::from([8])), sub_limb::<Native>(&[], 8));
+    }
+
+    #[test]
+    fn mul_limb_carries() {
+        let x = IntLimb::MAX;
+
+        assert_eq!(Vec::from([1, x, x - 1]), mul_limb::<Native>(&[x, x], x));
+        assert_eq!(Vec::<IntLimb>::new(), mul_limb::<Native>(&[x, x], 0));
+    }
+
+    #[test]
+    fn add_assign_reuses_buffer() {
+        let x = IntLimb::MAX;
+        let mut a = Vec::with_capacity(8);
+        a.extend_from_slice(&[x, x, 5]);
+        let ptr = a.as_ptr();
+
+        add_assign::<Native>(&mut a, &[1]);
+
+        assert_eq!(Vec::from([0, 0, 6]), a);
+        assert_eq!(ptr, a.as_ptr());
+    }
+
+    #[test]
+    fn add_assign_grows_on_carry() {
+        let x = IntLimb::MAX;
+        let mut a = Vec::from([x, x]);
+
+        add_assign::<Native>(&mut a, &[1]);
+
+        assert_eq!(Vec::from([0, 0, 1]), a);
+    }
+
+    #[test]
+    fn add_assign_shorter_left() {
+        let mut a = Vec::from([9]);
+
+        add_assign::<Native>(&mut a, &[1, 9, 9, 9, 9]);
+
+        assert_eq!(Vec::from([10, 9, 9, 9, 9]), a);
+    }
+
+    #[test]
+    fn sub_assign_in_place() {
+        let x = IntLimb::MAX;
+        let mut a = Vec::from([9, 8, 2]);
+        let mut b = Vec::from([1, 9, 9, 9, 9]);
+
+        assert_eq!(-1, sub_assign::<Native>(&mut a, &[1, 9, 9, 9, 9]));
+        assert_eq!(Vec::from([x - 7, 0, 7, 9, 9]), a);
+
+        assert_eq!(1, sub_assign::<Native>(&mut b, &[1, 9, 9]));
+        assert_eq!(Vec::from([0, 0, 0, 9, 9]), b);
+
+        assert_eq!(0, sub_assign::<Native>(&mut b, &[0, 0, 0, 9, 9]));
+        assert!(b.is_empty());
+    }
+
+    #[test]
+    fn mul_assign_by_limb() {
+        let mut a = Vec::from([2, 2, 2, 4, 5, 6]);
+
+        mul_assign::<Native>(&mut a, &[3]);
+
+        assert_eq!(Vec::from([6, 6, 6, 12, 15, 18]), a);
+    }
+
+    #[test]
+    fn div_assign_matches_div() {
+        let a: Vec<IntLimb> = (1..=90).map(|i| IntLimb::MAX / i).collect();
+
+        for &m in [1, 2, 30, 89, 90, 91].iter() {
+            let b: Vec<IntLimb> = (0..m as IntLimb)
+                .map(|i| i.wrapping_mul(0x9e37_79b9) | 1)
+                .collect();
+            let (q, r) = div::<Native>(&a, &b).unwrap();
+
+            let mut quotient = a.clone();
+            assert_eq!(
+                r,
+                div_assign::<Native>(&mut quotient, &b).unwrap(),
+                "{} limbs",
+                m
+            );
+            assert_eq!(q, quotient, "{} limbs", m);
+
+            let mut remainder = a.clone();
+            rem_assign::<Native>(&mut remainder, &b).unwrap();
+            assert_eq!(r, remainder, "{} limbs", m);
+        }
+
+        let mut a = a;
+        assert!(div_assign::<Native>(&mut a, &[]).is_err());
+        assert_eq!(90, a.len());
     }
 
     #[test]
@@ -317,7 +704,7 @@ mod tests {
 
         let c = Vec::from([6, 6, 6]);
 
-        assert_eq!(c, mul(&a, &b));
+        assert_eq!(c, mul::<Native>(&a, &b));
     }
 
     #[test]
@@ -327,7 +714,56 @@ mod tests {
 
         let c = Vec::from([6, 6, 6, 12, 15, 18]);
 
-        assert_eq!(c, mul(&a, &b));
+        assert_eq!(c, mul::<Native>(&a, &b));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn mul_aliased_operands_squares() {
+        for &n in [3, 30, 200, 500, 3000].iter() {
+            let a: Vec<IntLimb> = (1..=n as IntLimb)
+                .map(|i| i.wrapping_mul((IntLimb::MAX / 7) | 1))
+                .collect();
+            let b = a.clone();
+
+            assert_eq!(mul::<Native>(&a, &b), mul::<Native>(&a, &a), "{}", n);
+        }
+    }
+
+    #[test]
+    fn schoolbook_div_adds_back() {
+        let x = IntLimb::MAX;
+        let h = 1 << (IntLimb::BITS - 1);
+        let a = Vec::from([1, h, 0, x]);
+        let b = Vec::from([h - 1, h, h - 1]);
+
+        // The estimated quotient limb is one too large here.
+        let (q, r) = schoolbook_div::<Native>(&a, &b, &mut Workspace::new());
+        assert_eq!(Ordering::Less, cmp_repr::<Native>(&r, &b));
+        assert_eq!(a, add::<Native>(&mul::<Native>(&q, &b), &r));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn div_large_matches_schoolbook() -> Result<(), ArithmeticError> {
+        for &(m, n) in [(200, 70), (300, 150), (2500, 1200), (10500, 2100)].iter() {
+            let a: Vec<IntLimb> = (1..=m as IntLimb)
+                .map(|i| i.wrapping_mul((IntLimb::MAX / 7) | 1))
+                .collect();
+            let b: Vec<IntLimb> = (1..=n as IntLimb)
+                .map(|i| i.wrapping_mul(IntLimb::MAX / 5))
+                .collect();
+
+            assert_eq!(
+                schoolbook_div::<Native>(&a, &b, &mut Workspace::new()),
+                div::<Native>(&a, &b)?,
+                "{} / {}",
+                m,
+                n
+            );
+        }
+
+        Ok(())
     }
 
     #[test]
@@ -338,7 +774,7 @@ mod tests {
         let c = Vec::from([3, 3, 3, 3, 3]);
         let d: Vec<IntLimb> = Vec::from([]);
 
-        let (q, r) = div(&a, &b)?;
+        let (q, r) = div::<Native>(&a, &b)?;
 
         assert_eq!(c, q);
         assert_eq!(d, r);
@@ -354,7 +790,7 @@ mod tests {
         let c = [9223372036854775809, 4611686018427387904].to_vec();
         let d = Vec::from([1]);
 
-        let (q, r) = div(&a, &b)?;
+        let (q, r) = div::<Native>(&a, &b)?;
 
         assert_eq!(c, q);
         assert_eq!(d, r);
@@ -370,7 +806,7 @@ mod tests {
         let c = Vec::from([0, 0, 0, 0, 1]);
         let d: Vec<IntLimb> = Vec::from([]);
 
-        let (q, r) = div(&a, &b)?;
+        let (q, r) = div::<Native>(&a, &b)?;
 
         assert_eq!(c, q);
         assert_eq!(d, r);
@@ -383,10 +819,7 @@ mod tests {
         let a = Vec::from([0, 0, 0, 0, 1]);
         let b = Vec::from([]);
 
-        match div(&a, &b) {
-            Ok(_) => assert!(false, "Should throw error"),
-            Err(_) => assert!(true),
-        }
+        assert!(div::<Native>(&a, &b).is_err(), "Should throw error");
 
         Ok(())
     }
@@ -396,7 +829,7 @@ mod tests {
         let a = Vec::from([9, 9, 9]);
         let b = Vec::from([3, 3]);
 
-        let (q, r) = div(&a, &b)?;
+        let (q, r) = div::<Native>(&a, &b)?;
 
         let c = Vec::from([0, 3]);
         let d = Vec::from([9]);
@@ -412,7 +845,7 @@ mod tests {
         let a = Vec::from([1, 2, 1]);
         let b = Vec::from([1, 1]);
 
-        let (q, r) = div(&a, &b)?;
+        let (q, r) = div::<Native>(&a, &b)?;
 
         let c = Vec::from([1, 1]);
         let d: Vec<IntLimb> = Vec::from([]);
@@ -428,7 +861,7 @@ mod tests {
         let a = Vec::from([0, 0, 0, 9, 9, 1, 2]);
         let b = Vec::from([4, 3]);
 
-        let (q, r) = div(&a, &b)?;
+        let (q, r) = div::<Native>(&a, &b)?;
 
         let c = Vec::from([
             6756214907654938654,
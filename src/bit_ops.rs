@@ -0,0 +1,206 @@
+use crate::backend::LimbBackend;
+use crate::utils::trim_zeros;
+use crate::IntLimb;
+
+const LIMB_BITS: usize = IntLimb::BITS as usize;
+
+type LimbOp = fn(IntLimb, IntLimb) -> IntLimb;
+pub(crate) type BitwiseFunction = fn(&[IntLimb], bool, &[IntLimb], bool) -> (bool, Vec<IntLimb>);
+
+// Yields limbs of a sign-magnitude number as infinite two's complement,
+// negating on the fly so no dense copy of the operand is needed.
+struct TwosComplement<'a> {
+    limbs: &'a [IntLimb],
+    negative: bool,
+    carry: bool,
+}
+
+impl<'a> TwosComplement<'a> {
+    fn new(limbs: &'a [IntLimb], negative: bool) -> Self {
+        TwosComplement {
+            limbs,
+            negative,
+            carry: true,
+        }
+    }
+
+    fn extension(&self) -> IntLimb {
+        if self.negative {
+            IntLimb::MAX
+        } else {
+            0
+        }
+    }
+
+    // Has to be called for consecutive indices starting from 0.
+    fn limb(&mut self, i: usize) -> IntLimb {
+        let limb = self.limbs.get(i).copied().unwrap_or(0);
+        if !self.negative {
+            return limb;
+        }
+
+        let (v, carry) = (!limb).overflowing_add(self.carry as IntLimb);
+        self.carry = carry;
+        v
+    }
+}
+
+fn bitwise(
+    left: &[IntLimb],
+    left_negative: bool,
+    right: &[IntLimb],
+    right_negative: bool,
+    op: LimbOp,
+) -> (bool, Vec<IntLimb>) {
+    let mut l = TwosComplement::new(left, left_negative);
+    let mut r = TwosComplement::new(right, right_negative);
+
+    let negative = op(l.extension(), r.extension()) == IntLimb::MAX;
+    let n = usize::max(left.len(), right.len());
+
+    let mut dst = Vec::with_capacity(n + 1);
+    for i in 0..n {
+        dst.push(op(l.limb(i), r.limb(i)));
+    }
+
+    if negative {
+        dst.push(IntLimb::MAX);
+
+        let mut carry = true;
+        for limb in dst.iter_mut() {
+            let (v, c) = (!*limb).overflowing_add(carry as IntLimb);
+            *limb = v;
+            carry = c;
+        }
+    }
+
+    trim_zeros(&mut dst);
+    (negative, dst)
+}
+
+pub(crate) fn and(
+    left: &[IntLimb],
+    left_negative: bool,
+    right: &[IntLimb],
+    right_negative: bool,
+) -> (bool, Vec<IntLimb>) {
+    bitwise(left, left_negative, right, right_negative, |a, b| a & b)
+}
+
+pub(crate) fn or(
+    left: &[IntLimb],
+    left_negative: bool,
+    right: &[IntLimb],
+    right_negative: bool,
+) -> (bool, Vec<IntLimb>) {
+    bitwise(left, left_negative, right, right_negative, |a, b| a | b)
+}
+
+pub(crate) fn xor(
+    left: &[IntLimb],
+    left_negative: bool,
+    right: &[IntLimb],
+    right_negative: bool,
+) -> (bool, Vec<IntLimb>) {
+    bitwise(left, left_negative, right, right_negative, |a, b| a ^ b)
+}
+
+pub(crate) fn shl<B: LimbBackend>(x: &[IntLimb], bits: usize) -> Vec<IntLimb> {
+    if x.is_empty() {
+        return Vec::new();
+    }
+
+    let (limbs, shift) = (bits / LIMB_BITS, (bits % LIMB_BITS) as u32);
+    let n = x.len();
+    let mut dst = vec![0; limbs + n + 1];
+
+    dst[limbs + n] = B::shl(x, &mut dst[limbs..limbs + n], shift);
+    trim_zeros(&mut dst);
+    dst
+}
+
+// Returns shifted value and whether any non-zero bits were shifted out.
+pub(crate) fn shr<B: LimbBackend>(x: &[IntLimb], bits: usize) -> (Vec<IntLimb>, bool) {
+    let (limbs, shift) = (bits / LIMB_BITS, (bits % LIMB_BITS) as u32);
+    if limbs >= x.len() {
+        return (Vec::new(), !x.is_empty());
+    }
+
+    let mut dst = vec![0; x.len() - limbs];
+
+    let out = B::shr(&x[limbs..], &mut dst, shift);
+    let truncated = out != 0 || x[..limbs].iter().any(|&limb| limb != 0);
+
+    trim_zeros(&mut dst);
+    (dst, truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::Native;
+
+    #[test]
+    fn and_positive() {
+        let a = Vec::from([0b1100, 1]);
+        let b = Vec::from([0b1010]);
+
+        assert_eq!((false, Vec::from([0b1000])), and(&a, false, &b, false));
+    }
+
+    #[test]
+    fn and_with_negative_keeps_high_limbs() {
+        let a = Vec::from([0b1100, 1]);
+        let b = Vec::from([1]);
+
+        // a & -1
+        assert_eq!((false, Vec::from([0b1100, 1])), and(&a, false, &b, true));
+    }
+
+    #[test]
+    fn or_negative() {
+        let x = IntLimb::MAX;
+        let a = Vec::from([0, 1]);
+        let b = Vec::from([1]);
+
+        // 2^64 | -1 = -1, -2^64 | 1 = -(2^64 - 1)
+        assert_eq!((true, Vec::from([1])), or(&a, false, &b, true));
+        assert_eq!((true, Vec::from([x])), or(&a, true, &b, false));
+    }
+
+    #[test]
+    fn xor_negative_both() {
+        let a = Vec::from([5]);
+        let b = Vec::from([3]);
+
+        // -5 ^ -3 = 6
+        assert_eq!((false, Vec::from([6])), xor(&a, true, &b, true));
+    }
+
+    #[test]
+    fn xor_to_zero() {
+        let a = Vec::from([5, 7]);
+
+        assert_eq!((false, Vec::new()), xor(&a, true, &a, true));
+    }
+
+    #[test]
+    fn shl_by_limbs_and_bits() {
+        let (bits, top) = (IntLimb::BITS as usize, 1 << (IntLimb::BITS - 1));
+        let a = Vec::from([1, top]);
+
+        assert_eq!(Vec::from([0, 2, 0, 1]), shl::<Native>(&a, bits + 1));
+        assert_eq!(Vec::from([0, 0, 1, top]), shl::<Native>(&a, 2 * bits));
+        assert_eq!(Vec::<IntLimb>::new(), shl::<Native>(&[], 10));
+    }
+
+    #[test]
+    fn shr_by_limbs_and_bits() {
+        let a = Vec::from([1, 0, 6]);
+
+        assert_eq!((Vec::from([3]), true), shr::<Native>(&a, 129));
+        assert_eq!((Vec::from([0, 6]), true), shr::<Native>(&a, 64));
+        assert_eq!((Vec::from([6]), false), shr::<Native>(&[0, 0, 6], 128));
+        assert_eq!((Vec::new(), true), shr::<Native>(&a, 1000));
+    }
+}
@@ -1,92 +1,526 @@
-use crate::asm_ops::div_const;
-use crate::base_ops::{add, mul};
-use crate::utils::internal_repr;
+use crate::backend::{LimbBackend, Native};
+use crate::base_ops::{add, div, mul};
+use crate::errors::{ParseIntError, ParseIntErrorKind};
+use crate::utils::{internal_repr, trim_zeros};
 use crate::IntLimb;
 
-pub(crate) fn convert_to_internal(from: IntLimb, numbers: &[IntLimb]) -> Vec<IntLimb> {
-    let mut new_vec = Vec::new();
-    let bv = internal_repr(from);
+const LIMB_BITS: usize = IntLimb::BITS as usize;
+const DIVIDE_AND_CONQUER_THRESHOLD: usize = 32;
+
+// Number of bits per digit if `base` is a power of two.
+#[inline]
+fn power_of_two_bits(base: IntLimb) -> Option<usize> {
+    if base > 1 && base.is_power_of_two() {
+        Some(base.trailing_zeros() as usize)
+    } else {
+        None
+    }
+}
+
+// Digits map directly onto bits of the limbs, so packing them is linear.
+fn pack_digits(bits: usize, numbers: &[IntLimb]) -> Vec<IntLimb> {
+    let mut repr = vec![0; (numbers.len() * bits).div_ceil(LIMB_BITS)];
 
-    for number in numbers {
-        let v = internal_repr(*number);
-        new_vec = add(&mul(&new_vec, &bv), &v);
+    for (i, &digit) in numbers.iter().rev().enumerate() {
+        let (limb, offset) = ((i * bits) / LIMB_BITS, (i * bits) % LIMB_BITS);
+
+        repr[limb] |= digit << offset;
+        if offset + bits > LIMB_BITS {
+            repr[limb + 1] |= digit >> (LIMB_BITS - offset);
+        }
     }
 
-    new_vec
+    trim_zeros(&mut repr);
+    repr
 }
 
-pub(crate) fn convert_from_internal(to: IntLimb, numbers: &[IntLimb]) -> Vec<IntLimb> {
-    let mut nrs = numbers.to_vec();
-    let mut repr = Vec::new();
-
-    while !nrs.is_empty() {
-        let remainder = unsafe { div_const(nrs.as_mut_ptr(), to, nrs.len() as IntLimb) };
-        repr.push(remainder);
-        let mut i = 0;
-        for x in nrs.iter() {
-            if *x == 0 {
-                i += 1;
-            } else {
-                break;
+// Inverse of `pack_digits`, `numbers` are most significant limb first and digits
+// are returned least significant first.
+fn unpack_digits(bits: usize, numbers: &[IntLimb]) -> Vec<IntLimb> {
+    let n = numbers.len();
+    let total_bits = n * LIMB_BITS;
+    let mask = (1 << bits) - 1;
+
+    let mut digits = Vec::with_capacity(total_bits / bits + 1);
+    for bit in (0..total_bits).step_by(bits) {
+        let (i, offset) = (bit / LIMB_BITS, bit % LIMB_BITS);
+
+        let mut digit = numbers[n - 1 - i] >> offset;
+        if offset + bits > LIMB_BITS && i + 1 < n {
+            digit |= numbers[n - 2 - i] << (LIMB_BITS - offset);
+        }
+        digits.push(digit & mask);
+    }
+
+    trim_zeros(&mut digits);
+    digits
+}
+
+// Largest power of `base` that fits in a limb and its exponent, 10^19 for base 10.
+fn chunk_base(base: IntLimb) -> (IntLimb, usize) {
+    let (mut power, mut k) = (base, 1);
+    while let Some(p) = power.checked_mul(base) {
+        power = p;
+        k += 1;
+    }
+
+    (power, k)
+}
+
+// powers[i] = big_base^(2^i) for every 2^i < chunks.
+fn chunk_powers(big_base: IntLimb, chunks: usize) -> Vec<Vec<IntLimb>> {
+    let mut powers = vec![internal_repr(big_base)];
+    while (1 << powers.len()) < chunks {
+        let last = powers.last().unwrap();
+        powers.push(mul::<Native>(last, last));
+    }
+
+    powers
+}
+
+// `chunks` are digits in base `big_base`, most significant first.
+fn chunks_to_internal(
+    chunks: &[IntLimb],
+    big_base: IntLimb,
+    powers: &[Vec<IntLimb>],
+) -> Vec<IntLimb> {
+    let n = chunks.len();
+
+    if n <= DIVIDE_AND_CONQUER_THRESHOLD {
+        let mut repr: Vec<IntLimb> = Vec::with_capacity(n + 1);
+        for &chunk in chunks {
+            repr.push(0);
+            Native::mul_limb(&mut repr, big_base);
+            Native::add_limb(&mut repr, chunk);
+            trim_zeros(&mut repr);
+        }
+        return repr;
+    }
+
+    // Low half has 2^i chunks, so it is scaled by a cached power of the base.
+    let i = (usize::BITS - (n - 1).leading_zeros() - 1) as usize;
+    let (high, low) = chunks.split_at(n - (1 << i));
+
+    let high = chunks_to_internal(high, big_base, powers);
+    let low = chunks_to_internal(low, big_base, powers);
+
+    add::<Native>(&mul::<Native>(&high, &powers[i]), &low)
+}
+
+// Appends base `big_base` digits of `value` (least significant limb first) to `out`,
+// least significant first, padded with zeros to `count` digits if given.
+fn internal_to_chunks(
+    value: Vec<IntLimb>,
+    big_base: IntLimb,
+    powers: &[Vec<IntLimb>],
+    count: Option<usize>,
+    out: &mut Vec<IntLimb>,
+) {
+    let start = out.len();
+
+    let level = (0..powers.len())
+        .rev()
+        .find(|&i| powers[i].len() <= value.len() && count.is_none_or(|c| (1 << i) < c));
+
+    match level {
+        Some(i) if value.len() > DIVIDE_AND_CONQUER_THRESHOLD => {
+            let (q, r) = div::<Native>(&value, &powers[i]).unwrap();
+
+            internal_to_chunks(r, big_base, powers, Some(1 << i), out);
+            internal_to_chunks(q, big_base, powers, count.map(|c| c - (1 << i)), out);
+        }
+        _ => {
+            let mut nrs = value;
+            nrs.reverse();
+
+            while !nrs.is_empty() {
+                let remainder = Native::div_limb(&mut nrs, big_base);
+                out.push(remainder);
+
+                let zeros = nrs.iter().take_while(|&&x| x == 0).count();
+                nrs.drain(0..zeros);
             }
         }
-        if i > 0 {
-            nrs.drain(0..i);
+    }
+
+    if let Some(c) = count {
+        out.resize(start + c, 0);
+    }
+}
+
+pub(crate) fn convert_to_internal(from: IntLimb, numbers: &[IntLimb]) -> Vec<IntLimb> {
+    let digits_in_range = from >= 2 && numbers.iter().all(|&d| d < from);
+
+    if let Some(bits) = power_of_two_bits(from) {
+        if digits_in_range {
+            return pack_digits(bits, numbers);
+        }
+    }
+
+    if !digits_in_range {
+        let mut new_vec = Vec::new();
+        let bv = internal_repr(from);
+
+        for number in numbers {
+            let v = internal_repr(*number);
+            new_vec = add::<Native>(&mul::<Native>(&new_vec, &bv), &v);
+        }
+
+        return new_vec;
+    }
+
+    // Group digits into chunks that fit a limb, aligned to the least significant digit.
+    let (big_base, k) = chunk_base(from);
+    let first = numbers.len() % k;
+    let chunks: Vec<IntLimb> = std::iter::once(&numbers[..first])
+        .filter(|c| !c.is_empty())
+        .chain(numbers[first..].chunks(k))
+        .map(|c| c.iter().fold(0, |acc, &d| acc * from + d))
+        .collect();
+
+    let powers = chunk_powers(big_base, chunks.len());
+    chunks_to_internal(&chunks, big_base, &powers)
+}
+
+pub(crate) fn convert_from_internal(to: IntLimb, numbers: &[IntLimb]) -> Vec<IntLimb> {
+    if let Some(bits) = power_of_two_bits(to) {
+        return unpack_digits(bits, numbers);
+    }
+
+    let mut value = numbers.to_vec();
+    value.reverse();
+    trim_zeros(&mut value);
+
+    let (big_base, k) = chunk_base(to);
+    // Every limb holds a bit more than one chunk.
+    let powers = chunk_powers(big_base, value.len() + 1);
+
+    let mut chunks = Vec::new();
+    internal_to_chunks(value, big_base, &powers, None, &mut chunks);
+
+    let mut repr = Vec::with_capacity(chunks.len() * k);
+    for chunk in chunks {
+        let mut chunk = chunk;
+        for _ in 0..k {
+            repr.push(chunk % to);
+            chunk /= to;
         }
     }
 
+    trim_zeros(&mut repr);
     repr
 }
 
-pub(crate) fn convert_from_string(from: IntLimb, number: String) -> Vec<IntLimb> {
-    let numbers: Vec<_> = number
-        .chars()
-        .map(|x| x.to_digit(from as u32))
-        .flatten()
-        .map(|x| x as IntLimb)
-        .collect();
-    assert!(numbers.len() == number.len());
+// Parses digits of `number` in base `from`, allowing single `_` separators between digits.
+pub(crate) fn convert_from_string(
+    from: IntLimb,
+    number: &str,
+) -> Result<Vec<IntLimb>, ParseIntError> {
+    let radix = from as u32;
+    let is_digit = |c: Option<char>| matches!(c, Some(c) if c.is_digit(radix));
+
+    let mut numbers = Vec::with_capacity(number.len());
+    let mut previous = None;
+
+    for (i, c) in number.char_indices() {
+        let digit = c.to_digit(radix);
+
+        let valid = match digit {
+            Some(d) => {
+                numbers.push(d as IntLimb);
+                true
+            }
+            None => c == '_' && is_digit(previous) && is_digit(number[i + 1..].chars().next()),
+        };
 
-    convert_to_internal(from, &numbers)
+        if !valid {
+            return Err(ParseIntError {
+                kind: ParseIntErrorKind::InvalidDigit,
+                offset: i,
+            });
+        }
+        previous = Some(c);
+    }
+
+    if numbers.is_empty() {
+        return Err(ParseIntError {
+            kind: ParseIntErrorKind::Empty,
+            offset: number.len(),
+        });
+    }
+
+    Ok(convert_to_internal(from, &numbers))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::Native;
 
     #[test]
-    fn simple_base() {
+    fn simple_base() -> Result<(), ParseIntError> {
         let number = "10".to_string();
 
-        let r = convert_from_string(10, number);
+        let r = convert_from_string(10, &number)?;
 
         assert_eq!(vec![10], r);
+
+        Ok(())
     }
 
     #[test]
-    fn smaller_to_larger() {
+    fn smaller_to_larger() -> Result<(), ParseIntError> {
         let number = "1111111".to_string();
 
-        let r = convert_from_string(2, number);
+        let r = convert_from_string(2, &number)?;
 
         assert_eq!(vec![127], r);
+
+        Ok(())
     }
 
     #[test]
-    fn smaller_to_much_larger() {
+    fn smaller_to_much_larger() -> Result<(), ParseIntError> {
         let number = "100000".to_string();
 
-        let r = convert_from_string(10, number);
+        let r = convert_from_string(10, &number)?;
 
         assert_eq!(vec![100000], r);
+
+        Ok(())
     }
 
     #[test]
-    fn zero() {
+    fn zero() -> Result<(), ParseIntError> {
         let number = "00000000000".to_string();
 
-        let r = convert_from_string(10, number);
+        let r = convert_from_string(10, &number)?;
 
         assert_eq!(vec![0; 0], r);
+
+        Ok(())
+    }
+
+    #[test]
+    fn separators() -> Result<(), ParseIntError> {
+        assert_eq!(vec![1_000_000], convert_from_string(10, "1_000_000")?);
+        assert_eq!(vec![0b1010], convert_from_string(2, "10_10")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_separators() {
+        for (number, offset) in [("_1", 0), ("1_", 1), ("1__0", 1), ("12_a", 2)].iter() {
+            assert_eq!(
+                Err(ParseIntError {
+                    kind: ParseIntErrorKind::InvalidDigit,
+                    offset: *offset
+                }),
+                convert_from_string(10, number)
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_digit_offset() {
+        assert_eq!(
+            Err(ParseIntError {
+                kind: ParseIntErrorKind::InvalidDigit,
+                offset: 3
+            }),
+            convert_from_string(8, "1238")
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(
+            Err(ParseIntError {
+                kind: ParseIntErrorKind::Empty,
+                offset: 0
+            }),
+            convert_from_string(10, "")
+        );
+    }
+
+    fn multiply_add(from: IntLimb, numbers: &[IntLimb]) -> Vec<IntLimb> {
+        let mut new_vec = Vec::new();
+        for number in numbers {
+            new_vec = add::<Native>(
+                &mul::<Native>(&new_vec, &internal_repr(from)),
+                &internal_repr(*number),
+            );
+        }
+        new_vec
+    }
+
+    fn sample_digits(base: IntLimb, len: usize) -> Vec<IntLimb> {
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as IntLimb % base
+            })
+            .collect()
+    }
+
+    #[test]
+    fn power_of_two_bases_match_multiply_add() {
+        for &base in [2, 4, 8, 16, 32, 64, 1 << 20, 1 << (IntLimb::BITS - 1)].iter() {
+            for &len in [1, 5, 21, 64, 200].iter() {
+                let digits = sample_digits(base, len);
+
+                assert_eq!(
+                    multiply_add(base, &digits),
+                    convert_to_internal(base, &digits),
+                    "base {} len {}",
+                    base,
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn power_of_two_roundtrip() {
+        for &base in [2, 8, 16, 32, 1 << 7].iter() {
+            let mut digits = sample_digits(base, 301);
+            digits[0] = 1;
+
+            let mut repr = convert_to_internal(base, &digits);
+            repr.reverse();
+            let mut back = convert_from_internal(base, &repr);
+            back.reverse();
+
+            assert_eq!(digits, back, "base {}", base);
+        }
+    }
+
+    #[test]
+    fn power_of_two_from_internal() {
+        let x = IntLimb::MAX;
+
+        assert_eq!(
+            vec![0xf; 2 * IntLimb::BITS as usize / 4],
+            convert_from_internal(16, &[x, x])
+        );
+        assert_eq!(vec![0, 1], convert_from_internal(32, &[0, 32]));
+        assert_eq!(vec![1, 0, 4], convert_from_internal(8, &[0, 0o401]));
+        assert_eq!(vec![0; 0], convert_from_internal(2, &[0, 0]));
+    }
+
+    #[test]
+    fn out_of_range_digits_keep_multiply_add_semantics() {
+        assert_eq!(vec![35], convert_to_internal(16, &[2, 3]));
+        assert_eq!(vec![2 * 16 + 17], convert_to_internal(16, &[2, 17]));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn large_hex_is_linear() -> Result<(), ParseIntError> {
+        let hex = "f0e1d2c3".repeat(1 << 17);
+        let expected = 0xf0e1_d2c3_f0e1_d2c3_u64 as IntLimb;
+
+        let repr = convert_from_string(16, &hex)?;
+        assert_eq!((1 << 22) / IntLimb::BITS as usize, repr.len());
+        assert!(repr.iter().all(|&limb| limb == expected));
+
+        Ok(())
+    }
+
+    fn repeated_div(to: IntLimb, numbers: &[IntLimb]) -> Vec<IntLimb> {
+        let mut nrs = numbers.to_vec();
+        let mut digits = Vec::new();
+        while !nrs.is_empty() {
+            digits.push(Native::div_limb(&mut nrs, to));
+            let zeros = nrs.iter().take_while(|&&x| x == 0).count();
+            nrs.drain(0..zeros);
+        }
+        digits
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn chunk_base_fits_limb() {
+        assert_eq!((10_000_000_000_000_000_000, 19), chunk_base(10));
+        assert_eq!((3u64.pow(40) as IntLimb, 40), chunk_base(3));
+        assert_eq!((36u64.pow(12) as IntLimb, 12), chunk_base(36));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn divide_and_conquer_matches_multiply_add() {
+        for &base in [3, 7, 10, 36, 1_000_000_007].iter() {
+            for &len in [1, 19, 20, 700, 2000, 5003].iter() {
+                let digits = sample_digits(base, len);
+
+                assert_eq!(
+                    multiply_add(base, &digits),
+                    convert_to_internal(base, &digits),
+                    "base {} len {}",
+                    base,
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn divide_and_conquer_matches_repeated_div() {
+        for &base in [3, 7, 10, 36].iter() {
+            for &len in [1, 2, 33, 100, 257].iter() {
+                let mut numbers = sample_digits(IntLimb::MAX, len);
+                numbers[0] |= 1;
+
+                assert_eq!(
+                    repeated_div(base, &numbers),
+                    convert_from_internal(base, &numbers),
+                    "base {} len {}",
+                    base,
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn divide_and_conquer_keeps_inner_zeros() {
+        // 10^k has a run of zero chunks below the leading one.
+        for &k in [1, 19, 38, 1000, 4001].iter() {
+            let mut digits = vec![0; k + 1];
+            digits[0] = 1;
+
+            let mut repr = convert_to_internal(10, &digits);
+            repr.reverse();
+            let mut back = convert_from_internal(10, &repr);
+            back.reverse();
+
+            assert_eq!(digits, back, "k {}", k);
+        }
+    }
+
+    #[test]
+    fn decimal_roundtrip() {
+        for &base in [3, 10, 36].iter() {
+            let mut digits = sample_digits(base, 3001);
+            digits[0] = 1;
+
+            let mut repr = convert_to_internal(base, &digits);
+            repr.reverse();
+            let mut back = convert_from_internal(base, &repr);
+            back.reverse();
+
+            assert_eq!(digits, back, "base {}", base);
+        }
+    }
+
+    #[test]
+    fn non_power_of_two_out_of_range_digits() {
+        assert_eq!(vec![10 * 10 + 11], convert_to_internal(10, &[1, 0, 11]));
+        assert_eq!(vec![3], convert_to_internal(1, &[1, 1, 1]));
     }
 }
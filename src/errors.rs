@@ -12,3 +12,58 @@ impl Display for ArithmeticError {
         }
     }
 }
+
+impl std::error::Error for ArithmeticError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionError {
+    OutOfRange,
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            ConversionError::OutOfRange => write!(f, "Value out of range of the target type"),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseIntErrorKind {
+    Empty,
+    InvalidDigit,
+    InvalidRadix,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIntError {
+    pub(crate) kind: ParseIntErrorKind,
+    pub(crate) offset: usize,
+}
+
+impl ParseIntError {
+    pub fn kind(&self) -> ParseIntErrorKind {
+        self.kind
+    }
+
+    /// Byte offset in the parsed string at which the error was found.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Display for ParseIntError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self.kind {
+            ParseIntErrorKind::Empty => write!(f, "Cannot parse integer from empty string"),
+            ParseIntErrorKind::InvalidDigit => {
+                write!(f, "Invalid digit found at byte {}", self.offset)
+            }
+            ParseIntErrorKind::InvalidRadix => write!(f, "Radix must be 0 or in range 2..=36"),
+        }
+    }
+}
+
+impl std::error::Error for ParseIntError {}
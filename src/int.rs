@@ -1,180 +1,656 @@
 use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::ops;
+use std::str::FromStr;
 
+use crate::backend::{LimbBackend, Native};
 use crate::base_ops;
+use crate::bit_ops::{self, BitwiseFunction};
 use crate::conversions::{convert_from_internal, convert_from_string, convert_to_internal};
-use crate::utils::{cmp_repr, internal_repr};
+use crate::errors::{ArithmeticError, ParseIntError, ParseIntErrorKind};
+use crate::utils::cmp_repr;
+use crate::workspace::Workspace;
 use crate::IntLimb;
 
+mod magnitude;
+mod primitives;
+
+use magnitude::{inline_value, Magnitude, INLINE_LIMBS};
+
 #[derive(Debug, Clone)]
 pub struct Int {
     sign: i8,
-    repr: Vec<IntLimb>,
+    repr: Magnitude,
 }
 
 impl Int {
     pub fn zero() -> Int {
         Int {
             sign: 0,
-            repr: Vec::new(),
+            repr: Magnitude::new(),
         }
     }
 
     pub fn one() -> Int {
         Int {
             sign: 1,
-            repr: Vec::from([1]),
+            repr: Magnitude::from_u128(1),
         }
     }
 
+    /// Creates `value` with positive sign if `sign` is `true` and negative otherwise.
     pub fn new(value: IntLimb, sign: bool) -> Int {
-        let mut sign = if sign { 1 } else { 0 };
-        if value == 0 {
-            sign = 0;
-        }
-        let repr = internal_repr(value);
+        let sign = if sign { 1 } else { -1 };
 
-        Int { sign, repr }
+        Int::normalized(sign, Magnitude::from_u128(value as u128))
     }
 
+    /// Creates a number from `repr` digits in `base`, most significant first.
+    /// Any negative `sign` gives a negative number, any other a non-negative one.
     pub fn from_repr(base: IntLimb, repr: Vec<IntLimb>, sign: i8) -> Int {
-        let repr = convert_to_internal(base, &repr);
-        Int { sign, repr }
+        let sign = if sign < 0 { -1 } else { 1 };
+
+        Int::normalized(sign, convert_to_internal(base, &repr))
+    }
+
+    /// Panics unless `self` is in canonical form: zero has sign 0 and no limbs,
+    /// other values have sign -1 or 1 and no leading zero limbs.
+    pub fn assert_invariants(&self) {
+        assert!(
+            matches!(self.sign, -1..=1),
+            "Int sign out of range: {}",
+            self.sign
+        );
+        assert_eq!(
+            self.sign == 0,
+            self.repr.is_empty(),
+            "Int sign {} does not match magnitude {:?}",
+            self.sign,
+            self.repr
+        );
+        assert!(
+            self.repr.last() != Some(&0),
+            "Int magnitude has leading zero limbs: {:?}",
+            self.repr
+        );
+        assert_eq!(
+            self.repr.is_inline(),
+            self.repr.len() <= INLINE_LIMBS,
+            "Int magnitude of {} limbs stored in the wrong place",
+            self.repr.len()
+        );
+    }
+
+    /// Returns `self * self`, faster than a general multiplication.
+    pub fn square(&self) -> Int {
+        Int::normalized(self.sign * self.sign, base_ops::sqr::<Native>(&self.repr))
+    }
+
+    fn normalized(sign: i8, repr: impl Into<Magnitude>) -> Int {
+        let mut int = Int {
+            sign,
+            repr: repr.into(),
+        };
+        int.normalize();
+
+        int
+    }
+
+    fn small(sign: i8, value: u128) -> Int {
+        Int::normalized(sign, Magnitude::from_u128(value))
+    }
+
+    fn normalize(&mut self) {
+        self.repr.trim();
+        self.sign = if self.repr.is_empty() {
+            0
+        } else {
+            self.sign.signum()
+        };
+
+        if cfg!(debug_assertions) {
+            self.assert_invariants();
+        }
+    }
+
+    fn divide(&self, other: &Int, rounding: Rounding) -> Result<(Int, Int), ArithmeticError> {
+        divide::<Native>(
+            self.sign,
+            &self.repr,
+            other.sign,
+            &other.repr,
+            rounding,
+            &mut Workspace::new(),
+        )
+    }
+
+    /// Same as `self * other`, with the scratch space taken from `workspace` so repeated
+    /// calls can share it.
+    pub fn mul_with(&self, other: &Int, workspace: &mut Workspace) -> Int {
+        self.mul_with_backend::<Native>(other, workspace)
+    }
+
+    /// Same as [`Int::mul_with`], computed with the kernels of `B` instead of [`Native`],
+    /// to run and benchmark other kernel sets through the full algorithms.
+    pub fn mul_with_backend<B: LimbBackend>(&self, other: &Int, workspace: &mut Workspace) -> Int {
+        mul_signed::<B>(self, other.sign, &other.repr, workspace)
+    }
+
+    /// Same as [`Int::div_rem`], with the scratch space taken from `workspace`.
+    pub fn div_rem_with(
+        &self,
+        other: &Int,
+        workspace: &mut Workspace,
+    ) -> Result<(Int, Int), ArithmeticError> {
+        self.div_rem_with_backend::<Native>(other, workspace)
+    }
+
+    /// Same as [`Int::div_rem_with`], computed with the kernels of `B`.
+    pub fn div_rem_with_backend<B: LimbBackend>(
+        &self,
+        other: &Int,
+        workspace: &mut Workspace,
+    ) -> Result<(Int, Int), ArithmeticError> {
+        divide::<B>(
+            self.sign,
+            &self.repr,
+            other.sign,
+            &other.repr,
+            Rounding::Trunc,
+            workspace,
+        )
+    }
+
+    /// Returns quotient and remainder of `self / other` computed in a single pass,
+    /// rounding like Rust primitive integers, or `ArithmeticError::DividedByZero`
+    /// if `other` is zero.
+    pub fn div_rem(&self, other: &Int) -> Result<(Int, Int), ArithmeticError> {
+        self.divide(other, Rounding::Trunc)
+    }
+
+    /// Returns the quotient of `self / other`, or `Err(ArithmeticError::DividedByZero)`
+    /// if `other` is zero instead of panicking like `/`.
+    pub fn checked_div(&self, other: &Int) -> Result<Int, ArithmeticError> {
+        self.div_rem(other).map(|(q, _r)| q)
+    }
+
+    /// Returns the remainder of `self / other`, or `Err(ArithmeticError::DividedByZero)`
+    /// if `other` is zero instead of panicking like `%`.
+    pub fn checked_rem(&self, other: &Int) -> Result<Int, ArithmeticError> {
+        self.div_rem(other).map(|(_q, r)| r)
+    }
+
+    /// Quotient rounded towards zero, same as `/`.
+    pub fn div_trunc(&self, other: &Int) -> Int {
+        unwrap_division(self.divide(other, Rounding::Trunc)).0
+    }
+
+    /// Remainder with the sign of `self`, same as `%`.
+    pub fn rem_trunc(&self, other: &Int) -> Int {
+        unwrap_division(self.divide(other, Rounding::Trunc)).1
+    }
+
+    /// Quotient rounded towards negative infinity.
+    pub fn div_floor(&self, other: &Int) -> Int {
+        unwrap_division(self.divide(other, Rounding::Floor)).0
+    }
+
+    /// Remainder with the sign of `other`, matching `div_floor`.
+    pub fn mod_floor(&self, other: &Int) -> Int {
+        unwrap_division(self.divide(other, Rounding::Floor)).1
+    }
+
+    /// Quotient such that the remainder is always non-negative.
+    pub fn div_euclid(&self, other: &Int) -> Int {
+        unwrap_division(self.divide(other, Rounding::Euclid)).0
+    }
+
+    /// Non-negative remainder, matching `div_euclid`.
+    pub fn rem_euclid(&self, other: &Int) -> Int {
+        unwrap_division(self.divide(other, Rounding::Euclid)).1
     }
+
+    /// Quotient rounded towards positive infinity.
+    pub fn div_ceil(&self, other: &Int) -> Int {
+        unwrap_division(self.divide(other, Rounding::Ceil)).0
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Rounding {
+    Trunc,
+    Floor,
+    Ceil,
+    Euclid,
 }
 
-impl_op_ex!(+ |a: &Int, b: &Int| -> Int {
-    let sign;
-    let repr;
+fn divide<B: LimbBackend>(
+    a_sign: i8,
+    a: &[IntLimb],
+    b_sign: i8,
+    b: &[IntLimb],
+    rounding: Rounding,
+    workspace: &mut Workspace,
+) -> Result<(Int, Int), ArithmeticError> {
+    let sign = a_sign * b_sign;
+    let adjusts = |r_is_zero: bool| {
+        !r_is_zero
+            && match rounding {
+                Rounding::Trunc => false,
+                Rounding::Floor => sign < 0,
+                Rounding::Ceil => sign > 0,
+                Rounding::Euclid => a_sign < 0,
+            }
+    };
+    let r_sign = match rounding {
+        Rounding::Floor => b_sign,
+        Rounding::Ceil => -b_sign,
+        _ => 1,
+    };
+
+    if let (Some(x), Some(y)) = (inline_value(a), inline_value(b)) {
+        if y == 0 {
+            return Err(ArithmeticError::DividedByZero);
+        }
+
+        let (q, r) = (x / y, x % y);
+        return Ok(if adjusts(r == 0) {
+            (Int::small(sign, q + 1), Int::small(r_sign, y - r))
+        } else {
+            (Int::small(sign, q), Int::small(a_sign, r))
+        });
+    }
+
+    let (q, r) = base_ops::div_with::<B>(a, b, workspace)?;
+    if !adjusts(r.is_empty()) {
+        return Ok((Int::normalized(sign, q), Int::normalized(a_sign, r)));
+    }
+
+    // Quotient moves one step away from zero, remainder is replaced by |b| - |r|.
+    let q = base_ops::add_limb::<B>(&q, 1);
+    let (_, r) = base_ops::sub::<B>(b, &r);
 
-    if a.sign == b.sign {
-        repr = base_ops::add(&a.repr, &b.repr);
-        sign = a.sign;
+    Ok((Int::normalized(sign, q), Int::normalized(r_sign, r)))
+}
+
+fn unwrap_division<T>(result: Result<T, ArithmeticError>) -> T {
+    match result {
+        Ok(v) => v,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+// `a + b` on values that fit in a `u128`, `None` if the sum does not.
+fn add_small(a_sign: i8, a: u128, b_sign: i8, b: u128) -> Option<Int> {
+    if a_sign * b_sign >= 0 {
+        let sign = if a_sign != 0 { a_sign } else { b_sign };
+        a.checked_add(b).map(|sum| Int::small(sign, sum))
+    } else if a >= b {
+        Some(Int::small(a_sign, a - b))
     } else {
-        let (s, r) = base_ops::sub(&a.repr, &b.repr);
-        sign = s * a.sign;
-        repr = r;
+        Some(Int::small(b_sign, b - a))
     }
+}
 
-    Int {
-        sign,
-        repr,
+fn add_signed(a: &Int, b_sign: i8, b: &[IntLimb]) -> Int {
+    if let (Some(x), Some(y)) = (inline_value(&a.repr), inline_value(b)) {
+        if let Some(sum) = add_small(a.sign, x, b_sign, y) {
+            return sum;
+        }
     }
-});
 
-impl_op_ex!(-|a: &Int, b: &Int| -> Int {
-    let sign;
-    let repr;
+    if a.sign == 0 {
+        return Int::normalized(b_sign, b);
+    }
+
+    if b_sign == 0 {
+        return a.clone();
+    }
 
-    if a.sign == b.sign {
-        let (s, r) = base_ops::sub(&a.repr, &b.repr);
-        sign = s * a.sign;
-        repr = r;
+    if a.sign == b_sign {
+        let repr = if b.len() == 1 {
+            base_ops::add_limb::<Native>(&a.repr, b[0])
+        } else {
+            base_ops::add::<Native>(&a.repr, b)
+        };
+        Int::normalized(a.sign, repr)
     } else {
-        repr = base_ops::add(&a.repr, &b.repr);
-        sign = a.sign;
+        let (s, repr) = if b.len() == 1 {
+            base_ops::sub_limb::<Native>(&a.repr, b[0])
+        } else {
+            base_ops::sub::<Native>(&a.repr, b)
+        };
+        Int::normalized(s * a.sign, repr)
     }
+}
 
-    Int { sign, repr }
-});
+fn mul_signed<B: LimbBackend>(
+    a: &Int,
+    b_sign: i8,
+    b: &[IntLimb],
+    workspace: &mut Workspace,
+) -> Int {
+    if let (Some(x), Some(y)) = (inline_value(&a.repr), inline_value(b)) {
+        if let Some(product) = x.checked_mul(y) {
+            return Int::small(a.sign * b_sign, product);
+        }
+    }
 
-impl_op_ex!(*|a: &Int, b: &Int| -> Int {
-    let repr = base_ops::mul(&a.repr, &b.repr);
+    let repr = if b.len() == 1 {
+        base_ops::mul_limb::<B>(&a.repr, b[0])
+    } else {
+        base_ops::mul_with::<B>(&a.repr, b, workspace)
+    };
 
-    Int {
-        sign: a.sign * b.sign,
-        repr,
+    Int::normalized(a.sign * b_sign, repr)
+}
+
+fn cmp_signed(a: &Int, b_sign: i8, b: &[IntLimb]) -> Ordering {
+    match a.sign.cmp(&b_sign) {
+        Ordering::Equal if a.sign < 0 => cmp_repr::<Native>(b, &a.repr),
+        Ordering::Equal => cmp_repr::<Native>(&a.repr, b),
+        ord => ord,
     }
-});
+}
 
-impl_op_ex!(/ |a: &Int, b: &Int| -> Int {
-    let repr = base_ops::div(&a.repr, &b.repr);
+impl_op_ex!(+ |a: &Int, b: &Int| -> Int { add_signed(a, b.sign, &b.repr) });
 
-    match repr {
-        Err(_) => panic!("Division by zero"),
-        Ok(v) => {
-            let (q, _r) = v;
-            return Int {
-                sign: a.sign * b.sign,
-                repr: q,
-            };
+impl_op_ex!(-|a: &Int, b: &Int| -> Int { add_signed(a, -b.sign, &b.repr) });
+
+impl_op_ex!(-|a: &Int| -> Int { Int::normalized(-a.sign, a.repr.clone()) });
+
+fn add_assign_signed(a: &mut Int, b: &Int, b_sign: i8) {
+    if b_sign == 0 {
+        return;
+    }
+
+    if let (Some(x), Some(y)) = (inline_value(&a.repr), inline_value(&b.repr)) {
+        if let Some(sum) = add_small(a.sign, x, b_sign, y) {
+            *a = sum;
+            return;
+        }
+    }
+
+    if a.sign == 0 {
+        a.repr = b.repr.clone();
+        a.sign = b_sign;
+    } else if a.sign == b_sign {
+        base_ops::add_assign::<Native>(a.repr.vec_mut(), &b.repr);
+    } else {
+        a.sign *= base_ops::sub_assign::<Native>(a.repr.vec_mut(), &b.repr);
+    }
+
+    a.normalize();
+}
+
+impl_op_ex!(+= |a: &mut Int, b: &Int| { add_assign_signed(a, b, b.sign) });
+
+impl_op_ex!(-= |a: &mut Int, b: &Int| { add_assign_signed(a, b, -b.sign) });
+
+impl_op_ex!(*= |a: &mut Int, b: &Int| {
+    if let (Some(x), Some(y)) = (inline_value(&a.repr), inline_value(&b.repr)) {
+        if let Some(product) = x.checked_mul(y) {
+            *a = Int::small(a.sign * b.sign, product);
+            return;
         }
     }
+
+    base_ops::mul_assign::<Native>(a.repr.vec_mut(), &b.repr);
+    a.sign *= b.sign;
+    a.normalize();
+});
+
+impl_op_ex!(/= |a: &mut Int, b: &Int| {
+    if inline_value(&a.repr).is_some() {
+        *a = a.div_trunc(b);
+        return;
+    }
+
+    unwrap_division(base_ops::div_assign::<Native>(a.repr.vec_mut(), &b.repr));
+    a.sign *= b.sign;
+    a.normalize();
 });
 
-impl_op_ex!(% |a: &Int, b: &Int| -> Int {
-    let repr = base_ops::div(&a.repr, &b.repr);
+impl_op_ex!(%= |a: &mut Int, b: &Int| {
+    if inline_value(&a.repr).is_some() {
+        *a = a.rem_trunc(b);
+        return;
+    }
+
+    unwrap_division(base_ops::rem_assign::<Native>(a.repr.vec_mut(), &b.repr));
+    a.normalize();
+});
+
+impl_op_ex!(*|a: &Int, b: &Int| -> Int {
+    mul_signed::<Native>(a, b.sign, &b.repr, &mut Workspace::new())
+});
+
+impl_op_ex!(/ |a: &Int, b: &Int| -> Int { a.div_trunc(b) });
+
+impl_op_ex!(% |a: &Int, b: &Int| -> Int { a.rem_trunc(b) });
+
+fn bitwise(a: &Int, b: &Int, op: BitwiseFunction) -> Int {
+    let (negative, repr) = op(&a.repr, a.sign < 0, &b.repr, b.sign < 0);
+
+    Int::normalized(if negative { -1 } else { 1 }, repr)
+}
+
+impl_op_ex!(&|a: &Int, b: &Int| -> Int { bitwise(a, b, bit_ops::and) });
+
+impl_op_ex!(| |a: &Int, b: &Int| -> Int { bitwise(a, b, bit_ops::or) });
+
+impl_op_ex!(^ |a: &Int, b: &Int| -> Int { bitwise(a, b, bit_ops::xor) });
+
+impl_op_ex!(!|a: &Int| -> Int { -a - Int::one() });
+
+fn shift_amount(b: &Int) -> usize {
+    if b.sign < 0 {
+        panic!("Negative shift amount");
+    }
+
+    match b.repr.len() {
+        0 => 0,
+        1 => b.repr[0],
+        _ => usize::MAX,
+    }
+}
+
+// Right shifts saturate to 0 or -1, but a left shift by more than a limb's worth of bits would
+// need more memory than can be addressed.
+fn left_shift_amount(b: &Int) -> usize {
+    if b.sign > 0 && b.repr.len() > 1 {
+        panic!("Shift amount too large");
+    }
+
+    shift_amount(b)
+}
+
+fn shift_left(a: &Int, bits: usize) -> Int {
+    Int::normalized(a.sign, bit_ops::shl::<Native>(&a.repr, bits))
+}
+
+// Arithmetic shift, rounds towards negative infinity like primitive integers.
+fn shift_right(a: &Int, bits: usize) -> Int {
+    let (mut repr, truncated) = bit_ops::shr::<Native>(&a.repr, bits);
+    if a.sign < 0 && truncated {
+        repr = base_ops::add_limb::<Native>(&repr, 1);
+    }
+
+    Int::normalized(a.sign, repr)
+}
+
+impl_op_ex!(<< |a: &Int, b: usize| -> Int { shift_left(a, b) });
+
+impl_op_ex!(<< |a: &Int, b: &Int| -> Int { shift_left(a, left_shift_amount(b)) });
+
+impl_op_ex!(>> |a: &Int, b: usize| -> Int { shift_right(a, b) });
+
+impl_op_ex!(>> |a: &Int, b: &Int| -> Int { shift_right(a, shift_amount(b)) });
+
+impl_op_ex!(<<= |a: &mut Int, b: usize| { *a = shift_left(a, b) });
+
+impl_op_ex!(<<= |a: &mut Int, b: &Int| { *a = shift_left(a, left_shift_amount(b)) });
 
-    match repr {
-        Err(_) => panic!("Division by zero"),
-        Ok(v) => {
-            let (_q, r) = v;
-            return Int {
-                sign: b.sign,
-                repr: r,
+impl_op_ex!(>>= |a: &mut Int, b: usize| { *a = shift_right(a, b) });
+
+impl_op_ex!(>>= |a: &mut Int, b: &Int| { *a = shift_right(a, shift_amount(b)) });
+
+impl Int {
+    /// Parses an optionally signed number in `radix` (2 to 36), with single `_`
+    /// separators allowed between digits. Radix 0 detects `0x`, `0o` and `0b`
+    /// prefixes and falls back to decimal.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Int, ParseIntError> {
+        if radix == 1 || radix > 36 {
+            return Err(ParseIntError {
+                kind: ParseIntErrorKind::InvalidRadix,
+                offset: 0,
+            });
+        }
+
+        let (sign, mut start) = match s.as_bytes().first() {
+            Some(b'-') => (-1, 1),
+            Some(b'+') => (1, 1),
+            _ => (1, 0),
+        };
+
+        let mut radix = radix;
+        if radix == 0 {
+            radix = match s.get(start..start + 2) {
+                Some("0x") | Some("0X") => 16,
+                Some("0o") | Some("0O") => 8,
+                Some("0b") | Some("0B") => 2,
+                _ => 10,
             };
+            if radix != 10 {
+                start += 2;
+            }
         }
+
+        let repr =
+            convert_from_string(radix as IntLimb, &s[start..]).map_err(|e| ParseIntError {
+                offset: e.offset + start,
+                ..e
+            })?;
+
+        Ok(Int::normalized(sign, repr))
     }
-});
+}
+
+impl FromStr for Int {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Int, ParseIntError> {
+        Int::from_str_radix(s, 10)
+    }
+}
 
 impl From<(IntLimb, &str)> for Int {
     fn from(b_number: (IntLimb, &str)) -> Self {
-        let (from, mut number) = b_number;
-        let mut sign = 1;
+        let (from, number) = b_number;
 
-        if number.starts_with('-') {
-            sign = -1;
-            number = &number[1..number.len()];
+        match Int::from_str_radix(number, from as u32) {
+            Ok(v) => v,
+            Err(e) => panic!("{}", e),
         }
+    }
+}
 
-        let repr = convert_from_string(from, number.to_string());
+impl Int {
+    fn magnitude_digits(&self, radix: IntLimb, upper: bool) -> String {
+        if self.repr.is_empty() {
+            return "0".to_string();
+        }
 
-        Int { repr, sign }
+        let mut reversed_repr = self.repr.to_vec();
+        reversed_repr.reverse();
+
+        convert_from_internal(radix, &reversed_repr)
+            .into_iter()
+            .rev()
+            .map(|d| {
+                let c = std::char::from_digit(d as u32, radix as u32).unwrap();
+                if upper {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect()
+    }
+
+    fn fmt_radix(
+        &self,
+        f: &mut fmt::Formatter,
+        radix: IntLimb,
+        upper: bool,
+        prefix: &str,
+    ) -> fmt::Result {
+        f.pad_integral(self.sign >= 0, prefix, &self.magnitude_digits(radix, upper))
     }
 }
 
 impl fmt::Display for Int {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut reversed_repr = self.repr.to_vec();
+        self.fmt_radix(f, 10, false, "")
+    }
+}
 
-        if reversed_repr.is_empty() {
-            return write!(f, "0");
-        }
+impl fmt::LowerHex for Int {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_radix(f, 16, false, "0x")
+    }
+}
 
-        reversed_repr.reverse();
-        let dec = convert_from_internal(10, &reversed_repr);
-        let str_repr: String = dec
-            .into_iter()
-            .rev()
-            .map(|i| i.to_string())
-            .collect::<String>();
-        if self.sign < 0 {
-            write!(f, "-")?;
-        }
-        write!(f, "{}", str_repr)
+impl fmt::UpperHex for Int {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_radix(f, 16, true, "0x")
+    }
+}
+
+impl fmt::Octal for Int {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_radix(f, 8, false, "0o")
+    }
+}
+
+impl fmt::Binary for Int {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_radix(f, 2, false, "0b")
+    }
+}
+
+impl Ord for Int {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_signed(self, other.sign, &other.repr)
     }
 }
 
 impl PartialOrd for Int {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.sign != other.sign {
-            Some(self.sign.cmp(&other.sign))
-        } else {
-            Some(cmp_repr(&self.repr, &other.repr))
-        }
+        Some(self.cmp(other))
     }
 }
 
 impl PartialEq for Int {
     fn eq(&self, other: &Self) -> bool {
-        self.sign == other.sign && self.repr == other.repr
+        self.sign == other.sign && *self.repr == *other.repr
+    }
+}
+
+impl Eq for Int {}
+
+impl Hash for Int {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sign.hash(state);
+        self.repr[..].hash(state);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::Portable;
 
     #[test]
     fn add_work() {
@@ -216,6 +692,16 @@ mod tests {
         assert_eq!(res, (a * b).to_string());
     }
 
+    #[test]
+    fn square_matches_mul() {
+        let a = Int::from((10, "-23984702938714092873409218734091287340981273"));
+
+        assert_eq!(&a * &a.clone(), a.square());
+        assert_eq!(Int::from(9), Int::from(-3).square());
+        assert_eq!(Int::zero(), Int::zero().square());
+        assert_eq!(&a * &a, a.square());
+    }
+
     #[test]
     fn large_sub_work_1() {
         let a = Int::from((10, "7799476601844501102305401045355421728095916034908428628939400533506264447916217201951091768726426832809611399717962665235527581632337561345565876731297655978330048122528263802617579145330884277033721126690740252033624739555953519007966677932275408332444814480393921564897483434863147266404177295193463673134437480524283120048813928094190619063565741597202240555527704710095322108654955875834323969699677552327931114764233304498189385946028900821614101043955147837273960959858693159104052474052545966294837656123236171926462333281375840609496630746170728210589622812491257705367200333695787186777592776972582151020517514520080936119044661448381097757958480213763680841284503840223740406573893741569197709449734029759408021750362837503105517427901877382919632472001817020899894169610976558724670463100453925758713343098561069387092297405305057074020945281273407933123649935700085015071973281079057640444207234665373090020261758098028152107297100313596611385516490635734090800357133290059024369672834651"));
@@ -276,6 +762,296 @@ mod tests {
         assert_eq!(res, (a % b).to_string());
     }
 
+    #[test]
+    fn div_rem_work() -> Result<(), ArithmeticError> {
+        let a = Int::from((10, "192803740921837409812374098127340981273409821340987"));
+        let b = Int::from((10, "219837402893740912837409812734"));
+
+        let (q, r) = a.div_rem(&b)?;
+
+        assert_eq!("877028833055445496816", q.to_string());
+        assert_eq!("43230353436882553779668086043", r.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn div_rem_exact() -> Result<(), ArithmeticError> {
+        let a = Int::from((10, "102400"));
+        let b = Int::from((10, "1024"));
+
+        let (q, r) = a.div_rem(&b)?;
+
+        assert_eq!("100", q.to_string());
+        assert_eq!(Int::zero(), r);
+
+        Ok(())
+    }
+
+    #[test]
+    fn checked_div_by_zero() {
+        let a = Int::from((10, "102400"));
+
+        assert!(matches!(
+            a.checked_div(&Int::zero()),
+            Err(ArithmeticError::DividedByZero)
+        ));
+        assert!(matches!(
+            a.checked_rem(&Int::zero()),
+            Err(ArithmeticError::DividedByZero)
+        ));
+        assert!(matches!(
+            a.div_rem(&Int::zero()),
+            Err(ArithmeticError::DividedByZero)
+        ));
+    }
+
+    #[test]
+    fn checked_div_work() -> Result<(), ArithmeticError> {
+        let a = Int::from((10, "102400"));
+        let b = Int::from((10, "124"));
+
+        assert_eq!("825", a.checked_div(&b)?.to_string());
+        assert_eq!("100", a.checked_rem(&b)?.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Division by zero")]
+    fn div_by_zero_panics() {
+        let _ = Int::one() / Int::zero();
+    }
+
+    #[test]
+    fn division_rounding_modes() {
+        let cases: [(i64, i64); 8] = [
+            (7, 2),
+            (-7, 2),
+            (7, -2),
+            (-7, -2),
+            (6, 3),
+            (-6, 3),
+            (0, -5),
+            (1, 5),
+        ];
+
+        fn int(v: i64) -> Int {
+            if v == 0 {
+                Int::zero()
+            } else {
+                Int::from((10, v.to_string().as_str()))
+            }
+        }
+
+        for &(a, b) in cases.iter() {
+            let (x, y) = (int(a), int(b));
+
+            assert_eq!(int(a / b), &x / &y, "{} / {}", a, b);
+            assert_eq!(int(a % b), &x % &y, "{} % {}", a, b);
+            assert_eq!(int(a / b), x.div_trunc(&y), "{} div_trunc {}", a, b);
+            assert_eq!(int(a % b), x.rem_trunc(&y), "{} rem_trunc {}", a, b);
+            assert_eq!(
+                int(a.div_euclid(b)),
+                x.div_euclid(&y),
+                "{} div_euclid {}",
+                a,
+                b
+            );
+            assert_eq!(
+                int(a.rem_euclid(b)),
+                x.rem_euclid(&y),
+                "{} rem_euclid {}",
+                a,
+                b
+            );
+
+            let floor = (a as f64 / b as f64).floor() as i64;
+            let ceil = (a as f64 / b as f64).ceil() as i64;
+            assert_eq!(int(floor), x.div_floor(&y), "{} div_floor {}", a, b);
+            assert_eq!(int(a - floor * b), x.mod_floor(&y), "{} mod_floor {}", a, b);
+            assert_eq!(int(ceil), x.div_ceil(&y), "{} div_ceil {}", a, b);
+        }
+    }
+
+    #[test]
+    fn div_floor_carries_into_next_limb() {
+        let a = Int::from((10, "-221360928884514619391"));
+        let b = Int::from((10, "2"));
+
+        assert_eq!("-110680464442257309696", a.div_floor(&b).to_string());
+        assert_eq!("1", a.mod_floor(&b).to_string());
+    }
+
+    #[test]
+    fn zero_is_canonical() {
+        let zeros = [
+            Int::from((10, "0")),
+            Int::from((10, "-0")),
+            Int::from((10, "0000")),
+            Int::new(0, true),
+            Int::new(0, false),
+            Int::from_repr(10, vec![0, 0], -1),
+            Int::from_repr(10, vec![], 5),
+            Int::one() - Int::one(),
+            -Int::zero(),
+            Int::zero() * Int::new(5, false),
+        ];
+
+        for zero in zeros.iter() {
+            zero.assert_invariants();
+            assert_eq!(&Int::zero(), zero);
+        }
+    }
+
+    #[test]
+    fn constructors_are_canonical() {
+        assert_eq!(Int::from((10, "-5")), Int::new(5, false));
+        assert_eq!(Int::from((10, "5")), Int::from_repr(10, vec![5], 100));
+        assert_eq!(Int::from((10, "-5")), Int::from_repr(10, vec![5], -100));
+        assert_eq!(Int::from((10, "5")), Int::from_repr(10, vec![0, 5], 0));
+    }
+
+    #[test]
+    fn add_sub_with_zero() {
+        let five = Int::new(5, true);
+        let minus_five = Int::new(5, false);
+
+        assert_eq!(five, Int::zero() + &five);
+        assert_eq!(five, &five + Int::zero());
+        assert_eq!(minus_five, Int::zero() - &five);
+        assert_eq!(five, Int::zero() - &minus_five);
+        assert_eq!(five, &five - Int::zero());
+        assert_eq!(Int::zero(), &five + &minus_five);
+        assert_eq!(minus_five, -&five);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match magnitude")]
+    fn assert_invariants_rejects_signed_zero() {
+        Int {
+            sign: -1,
+            repr: Magnitude::new(),
+        }
+        .assert_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "sign out of range")]
+    fn assert_invariants_rejects_large_sign() {
+        Int {
+            sign: 3,
+            repr: vec![1].into(),
+        }
+        .assert_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "leading zero limbs")]
+    fn assert_invariants_rejects_leading_zeros() {
+        Int {
+            sign: 1,
+            repr: vec![1, 0].into(),
+        }
+        .assert_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "stored in the wrong place")]
+    fn assert_invariants_rejects_short_heap_magnitude() {
+        Int {
+            sign: 1,
+            repr: Magnitude::Heap(vec![1]),
+        }
+        .assert_invariants();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn workspace_reused_across_sizes() -> Result<(), ArithmeticError> {
+        let mut workspace = Workspace::new();
+        let mut a = Int::from((16, "f123456789abcdef0fedcba987654321"));
+
+        for _ in 0..6 {
+            let b = a.square() + 7u8;
+
+            assert_eq!(&a * &b, a.mul_with(&b, &mut workspace));
+            assert_eq!(b.div_rem(&a)?, b.div_rem_with(&a, &mut workspace)?);
+            a = &b * &a - 1u8;
+        }
+        assert!(workspace.capacity() > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn portable_backend_matches_native() -> Result<(), ArithmeticError> {
+        let mut workspace = Workspace::new();
+        let a = Int::from((16, "f123456789abcdef0fedcba987654321".repeat(40).as_str()));
+        let b = -Int::from((16, "123456789abcdef".repeat(23).as_str()));
+
+        assert_eq!(&a * &b, a.mul_with_backend::<Portable>(&b, &mut workspace));
+        assert_eq!(
+            a.div_rem(&b)?,
+            a.div_rem_with_backend::<Portable>(&b, &mut workspace)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn u128_fast_paths_at_the_boundary() {
+        let max = Int::from(u128::MAX);
+
+        assert_eq!(
+            "340282366920938463463374607431768211456",
+            (&max + 1u8).to_string()
+        );
+        assert_eq!(
+            "-340282366920938463463374607431768211456",
+            (-&max - 1u8).to_string()
+        );
+        assert_eq!(Int::from(u128::MAX - 1), &max - 1u8);
+        assert_eq!(
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025",
+            (&max * &max).to_string()
+        );
+
+        let mut acc = max.clone();
+        acc += Int::one();
+        acc -= Int::one();
+        assert_eq!(max, acc);
+        acc *= &max;
+        acc /= &max;
+        assert_eq!(max, acc);
+        acc.assert_invariants();
+    }
+
+    #[test]
+    fn small_division_matches_general_path() {
+        type Op = fn(&Int, &Int) -> Int;
+        let values: [i64; 6] = [7, -7, 2, -2, 13, -5];
+
+        for &a in values.iter() {
+            for &b in values.iter() {
+                let (x, y) = (Int::from(a), Int::from(b));
+                // Scaled past the inline size, same quotients and scaled remainders.
+                let (big_x, big_y) = (&x << 200, &y << 200);
+
+                let modes: [(Op, Op); 4] = [
+                    (Int::div_trunc, Int::rem_trunc),
+                    (Int::div_floor, Int::mod_floor),
+                    (Int::div_euclid, Int::rem_euclid),
+                    (Int::div_ceil, |a, b| a - b * a.div_ceil(b)),
+                ];
+                for (div, rem) in modes.iter() {
+                    assert_eq!(div(&big_x, &big_y), div(&x, &y), "{} / {}", a, b);
+                    assert_eq!(rem(&big_x, &big_y), rem(&x, &y) << 200, "{} % {}", a, b);
+                }
+            }
+        }
+    }
+
     #[test]
     fn factorial() {
         fn fact(n: IntLimb) -> Int {
@@ -297,6 +1073,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn ordering() {
         let a = Int::from((10, "192803740921837409812374098127340981273409821340987"));
         let b = Int::from((10, "219837402893740912837409812734"));
@@ -309,4 +1086,308 @@ mod tests {
         assert_eq!(true, b == b);
         assert_eq!(true, a != b);
     }
+
+    #[test]
+    fn ordering_negative() {
+        let a = Int::from((10, "-5"));
+        let b = Int::from((10, "-3"));
+        let c = Int::from((10, "-192803740921837409812374098127340981273409821340987"));
+
+        assert!(a < b);
+        assert!(c < a);
+        assert!(c < Int::zero());
+        assert!(Int::zero() < Int::one());
+        assert_eq!(Ordering::Equal, a.cmp(&Int::new(5, false)));
+    }
+
+    #[test]
+    fn sort_mixed_signs() {
+        let mut values: Vec<Int> = ["12", "-3", "0", "-18446744073709551616", "3", "-4", "0"]
+            .iter()
+            .map(|v| Int::from((10, *v)))
+            .collect();
+
+        values.sort();
+
+        let sorted: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            vec!["-18446744073709551616", "-4", "-3", "0", "0", "3", "12"],
+            sorted
+        );
+    }
+
+    #[test]
+    fn usable_as_map_key() {
+        use std::collections::{BTreeMap, HashMap};
+
+        let mut btree = BTreeMap::new();
+        let mut hash = HashMap::new();
+
+        for (i, v) in ["7", "-7", "0", "-0", "7"].iter().enumerate() {
+            btree.insert(Int::from((10, *v)), i);
+            hash.insert(Int::from((10, *v)), i);
+        }
+
+        assert_eq!(3, btree.len());
+        assert_eq!(3, hash.len());
+        assert_eq!(Some(&3), hash.get(&Int::zero()));
+        assert_eq!(Some(&4), btree.get(&Int::new(7, true)));
+        assert_eq!(
+            vec![Int::new(7, false), Int::zero(), Int::new(7, true)],
+            btree.keys().cloned().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn bitwise_matches_i128() {
+        let values: [i128; 10] = [
+            0,
+            1,
+            -1,
+            12345,
+            -12345,
+            i64::MAX as i128 + 7,
+            -(u64::MAX as i128) - 1,
+            0x5555_5555_5555_5555_aaaa_aaaa_aaaa_aaaa,
+            -0x7fff_0000_ffff_0000_1234_5678_9abc_def0,
+            i128::MIN + 1,
+        ];
+
+        fn int(v: i128) -> Int {
+            Int::from((10, v.to_string().as_str()))
+        }
+
+        for &a in values.iter() {
+            assert_eq!(int(!a), !int(a), "!{}", a);
+
+            for &b in values.iter() {
+                let (x, y) = (int(a), int(b));
+
+                assert_eq!(int(a & b), &x & &y, "{} & {}", a, b);
+                assert_eq!(int(a | b), &x | &y, "{} | {}", a, b);
+                assert_eq!(int(a ^ b), &x ^ &y, "{} ^ {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn shifts_match_i128() {
+        let values: [i128; 8] = [
+            0,
+            1,
+            -1,
+            -12345,
+            0x1234_5678_9abc_def0,
+            -(u64::MAX as i128) - 1,
+            0x5555_5555_aaaa_aaaa_aaaa_aaaa,
+            -0x7fff_0000_ffff_0000_1234_5678,
+        ];
+
+        fn int(v: i128) -> Int {
+            Int::from((10, v.to_string().as_str()))
+        }
+
+        for &a in values.iter() {
+            for &k in [0, 1, 7, 63, 64, 65, 100, 127].iter() {
+                assert_eq!(int(a >> k), int(a) >> k, "{} >> {}", a, k);
+                assert_eq!(int(a >> k), int(a) >> Int::new(k, true), "{} >> {}", a, k);
+            }
+
+            for &k in [0, 1, 7, 15, 31].iter() {
+                assert_eq!(int(a << k), int(a) << k, "{} << {}", a, k);
+                assert_eq!(int(a << k), &int(a) << &Int::new(k, true), "{} << {}", a, k);
+            }
+        }
+    }
+
+    #[test]
+    fn shift_by_many_limbs() {
+        let a = Int::from((10, "-192803740921837409812374098127340981273409821340987"));
+
+        assert_eq!(a, (&a << 1000) >> 1000);
+        assert_eq!(Int::new(1, false), &a >> 100_000);
+        assert_eq!(
+            Int::zero(),
+            -&a >> Int::from((10, "100000000000000000000000"))
+        );
+    }
+
+    #[test]
+    fn shift_assign() {
+        let mut a = Int::from((10, "-7"));
+
+        a <<= 70;
+        assert_eq!(Int::from((10, "-8264141345021879123968")), a);
+
+        a >>= &Int::new(72, true);
+        assert_eq!(Int::from((10, "-2")), a);
+    }
+
+    #[test]
+    #[should_panic(expected = "Negative shift amount")]
+    fn shift_by_negative_panics() {
+        let _ = Int::one() << Int::new(1, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Shift amount too large")]
+    fn shift_left_by_many_limbs_panics() {
+        let _ = Int::one() << Int::from((10, "100000000000000000000000"));
+    }
+
+    #[test]
+    fn compound_assignment_matches_binary_ops() {
+        let values = [
+            "0",
+            "1",
+            "-1",
+            "18446744073709551615",
+            "-18446744073709551616",
+            "23984702938714092873409218734091287340981273",
+            "-21398470829374098127340821734",
+        ];
+
+        for a in values.iter() {
+            for b in values.iter() {
+                let (x, y) = (Int::from((10, *a)), Int::from((10, *b)));
+
+                let mut r = x.clone();
+                r += &y;
+                assert_eq!(&x + &y, r, "{} += {}", a, b);
+
+                let mut r = x.clone();
+                r -= &y;
+                assert_eq!(&x - &y, r, "{} -= {}", a, b);
+
+                let mut r = x.clone();
+                r *= &y;
+                assert_eq!(&x * &y, r, "{} *= {}", a, b);
+
+                if y != Int::zero() {
+                    let mut r = x.clone();
+                    r /= &y;
+                    assert_eq!(&x / &y, r, "{} /= {}", a, b);
+
+                    let mut r = x.clone();
+                    r %= y.clone();
+                    assert_eq!(&x % &y, r, "{} %= {}", a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn accumulate_in_place() {
+        let mut acc = Int::zero();
+        let step = Int::from((10, "18446744073709551615"));
+
+        for _ in 0..1000 {
+            acc += &step;
+        }
+        for _ in 0..250 {
+            acc -= &step;
+        }
+
+        assert_eq!(Int::from((10, "13835058055282163711250")), acc);
+    }
+
+    #[test]
+    fn from_str_work() -> Result<(), ParseIntError> {
+        assert_eq!(Int::new(123, true), "123".parse::<Int>()?);
+        assert_eq!(Int::new(123, true), "+123".parse::<Int>()?);
+        assert_eq!(Int::new(123, false), "-1_2_3".parse::<Int>()?);
+        assert_eq!(Int::zero(), "-0".parse::<Int>()?);
+        assert_eq!(
+            Int::from((10, "23984702938714092873409218734091287340981273")),
+            "23_984_702_938_714_092_873_409_218_734_091_287_340_981_273".parse::<Int>()?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_radix_prefixes() -> Result<(), ParseIntError> {
+        assert_eq!(Int::new(255, true), Int::from_str_radix("0xff", 0)?);
+        assert_eq!(Int::new(255, false), Int::from_str_radix("-0XFF", 0)?);
+        assert_eq!(Int::new(8, true), Int::from_str_radix("+0o10", 0)?);
+        assert_eq!(Int::new(5, true), Int::from_str_radix("0b1_01", 0)?);
+        assert_eq!(Int::new(10, true), Int::from_str_radix("010", 0)?);
+        assert_eq!(Int::new(35, true), Int::from_str_radix("z", 36)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_radix_errors() {
+        let cases = [
+            ("", 10, ParseIntErrorKind::Empty, 0),
+            ("-", 10, ParseIntErrorKind::Empty, 1),
+            ("0x", 0, ParseIntErrorKind::Empty, 2),
+            ("12a4", 10, ParseIntErrorKind::InvalidDigit, 2),
+            ("-0b102", 0, ParseIntErrorKind::InvalidDigit, 5),
+            ("0xff", 16, ParseIntErrorKind::InvalidDigit, 1),
+            ("+-1", 10, ParseIntErrorKind::InvalidDigit, 1),
+            ("1__0", 10, ParseIntErrorKind::InvalidDigit, 1),
+            (" 1", 10, ParseIntErrorKind::InvalidDigit, 0),
+            ("1", 1, ParseIntErrorKind::InvalidRadix, 0),
+            ("1", 37, ParseIntErrorKind::InvalidRadix, 0),
+        ];
+
+        for (s, radix, kind, offset) in cases.iter() {
+            let err = Int::from_str_radix(s, *radix).unwrap_err();
+
+            assert_eq!(*kind, err.kind(), "{:?}", s);
+            assert_eq!(*offset, err.offset(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid digit found at byte 1")]
+    fn from_tuple_panics_on_invalid_digit() {
+        let _ = Int::from((10, "1x"));
+    }
+
+    #[test]
+    fn radix_formatting() {
+        let a = Int::from((10, "-255"));
+        let b = Int::from((10, "340282366920938463463374607431768211455"));
+
+        assert_eq!("-ff", format!("{:x}", a));
+        assert_eq!("-0xff", format!("{:#x}", a));
+        assert_eq!("-0xFF", format!("{:#X}", a));
+        assert_eq!("-0o377", format!("{:#o}", a));
+        assert_eq!("-0b11111111", format!("{:#b}", a));
+        assert_eq!("ffffffffffffffffffffffffffffffff", format!("{:x}", b));
+        assert_eq!("0", format!("{:x}", Int::zero()));
+        assert_eq!("0b0", format!("{:#b}", Int::zero()));
+    }
+
+    #[test]
+    fn formatter_flags() {
+        let a = Int::from((10, "-255"));
+        let b = Int::from((10, "255"));
+
+        assert_eq!("      -255", format!("{:>10}", a));
+        assert_eq!("-255      ", format!("{:<10}", a));
+        assert_eq!("***-255***", format!("{:*^10}", a));
+        assert_eq!("+255", format!("{:+}", b));
+        assert_eq!("-255", format!("{:+}", a));
+        assert_eq!("-000000255", format!("{:010}", a));
+        assert_eq!("+0x00000ff", format!("{:+#010x}", b));
+        assert_eq!("      0xFF", format!("{:#10X}", b));
+        assert_eq!("-0o0000377", format!("{:#010o}", a));
+        assert_eq!("+0", format!("{:+}", Int::zero()));
+
+        // Matches formatting of primitives.
+        for spec in [-255i64, 0, 255].iter() {
+            let v = Int::from(*spec);
+
+            assert_eq!(format!("{:>+40}", spec), format!("{:>+40}", v));
+            assert_eq!(
+                format!("{:#020x}", spec.abs()),
+                format!("{:#020x}", Int::from(spec.abs()))
+            );
+            assert_eq!(format!("{:020}", spec), format!("{:020}", v));
+        }
+    }
 }
@@ -0,0 +1,181 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use crate::IntLimb;
+
+// Magnitudes of up to this many limbs are stored inline, they always fit in a `u128`.
+pub(super) const INLINE_LIMBS: usize = (u128::BITS / IntLimb::BITS) as usize;
+
+// Limbs of an `Int`, least significant first. Values that fit in `INLINE_LIMBS` limbs
+// don't allocate, longer ones spill to the heap.
+#[derive(Clone)]
+pub(super) enum Magnitude {
+    Inline {
+        len: u8,
+        limbs: [IntLimb; INLINE_LIMBS],
+    },
+    Heap(Vec<IntLimb>),
+}
+
+impl Magnitude {
+    pub(super) fn new() -> Magnitude {
+        Magnitude::Inline {
+            len: 0,
+            limbs: [0; INLINE_LIMBS],
+        }
+    }
+
+    pub(super) fn from_u128(value: u128) -> Magnitude {
+        let mut limbs = [0; INLINE_LIMBS];
+        let mut len = 0;
+        let mut value = value;
+
+        while value != 0 {
+            limbs[len] = value as IntLimb;
+            value >>= IntLimb::BITS;
+            len += 1;
+        }
+
+        Magnitude::Inline {
+            len: len as u8,
+            limbs,
+        }
+    }
+
+    pub(super) fn is_inline(&self) -> bool {
+        matches!(self, Magnitude::Inline { .. })
+    }
+
+    // The limbs as a `Vec` the in-place kernels can grow, spilling inline ones.
+    pub(super) fn vec_mut(&mut self) -> &mut Vec<IntLimb> {
+        if let Magnitude::Inline { .. } = self {
+            let mut spilled = Vec::with_capacity(2 * INLINE_LIMBS);
+            spilled.extend_from_slice(self);
+            *self = Magnitude::Heap(spilled);
+        }
+
+        match self {
+            Magnitude::Heap(v) => v,
+            Magnitude::Inline { .. } => unreachable!(),
+        }
+    }
+
+    // Drops leading zero limbs and moves the rest inline if it fits.
+    pub(super) fn trim(&mut self) {
+        match self {
+            Magnitude::Inline { len, limbs } => {
+                while *len > 0 && limbs[*len as usize - 1] == 0 {
+                    *len -= 1;
+                }
+            }
+            Magnitude::Heap(v) => {
+                while v.last() == Some(&0) {
+                    v.pop();
+                }
+                if v.len() <= INLINE_LIMBS {
+                    *self = Magnitude::from(std::mem::take(v));
+                }
+            }
+        }
+    }
+}
+
+// Value of `limbs` if it is short enough to be stored inline.
+pub(super) fn inline_value(limbs: &[IntLimb]) -> Option<u128> {
+    if limbs.len() > INLINE_LIMBS {
+        return None;
+    }
+
+    Some(
+        limbs
+            .iter()
+            .rev()
+            .fold(0, |acc, &limb| (acc << IntLimb::BITS) | limb as u128),
+    )
+}
+
+impl From<&[IntLimb]> for Magnitude {
+    fn from(v: &[IntLimb]) -> Magnitude {
+        if v.len() > INLINE_LIMBS {
+            return Magnitude::Heap(v.to_vec());
+        }
+
+        let mut limbs = [0; INLINE_LIMBS];
+        limbs[..v.len()].copy_from_slice(v);
+        Magnitude::Inline {
+            len: v.len() as u8,
+            limbs,
+        }
+    }
+}
+
+impl From<Vec<IntLimb>> for Magnitude {
+    fn from(v: Vec<IntLimb>) -> Magnitude {
+        if v.len() > INLINE_LIMBS {
+            Magnitude::Heap(v)
+        } else {
+            Magnitude::from(&v[..])
+        }
+    }
+}
+
+impl Deref for Magnitude {
+    type Target = [IntLimb];
+
+    fn deref(&self) -> &[IntLimb] {
+        match self {
+            Magnitude::Inline { len, limbs } => &limbs[..*len as usize],
+            Magnitude::Heap(v) => v,
+        }
+    }
+}
+
+impl DerefMut for Magnitude {
+    fn deref_mut(&mut self) -> &mut [IntLimb] {
+        match self {
+            Magnitude::Inline { len, limbs } => &mut limbs[..*len as usize],
+            Magnitude::Heap(v) => v,
+        }
+    }
+}
+
+// Same output as the `Vec` it replaced.
+impl fmt::Debug for Magnitude {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_vecs_go_inline() {
+        assert!(Magnitude::from(vec![1; INLINE_LIMBS]).is_inline());
+        assert!(!Magnitude::from(vec![1; INLINE_LIMBS + 1]).is_inline());
+        assert_eq!([0; 0], *Magnitude::new());
+    }
+
+    #[test]
+    fn u128_round_trip() {
+        for v in [0, 1, IntLimb::MAX as u128, 1 << 100, u128::MAX] {
+            let m = Magnitude::from_u128(v);
+            assert!(m.is_inline());
+            assert_eq!(Some(v), inline_value(&m));
+        }
+        assert_eq!(None, inline_value(&[1; INLINE_LIMBS + 1]));
+    }
+
+    #[test]
+    fn spill_and_trim_back() {
+        let mut m = Magnitude::from_u128(5);
+        m.vec_mut().extend_from_slice(&[0; INLINE_LIMBS + 1]);
+        assert!(!m.is_inline());
+        assert_eq!(INLINE_LIMBS + 2, m.len());
+
+        m.trim();
+        assert!(m.is_inline());
+        assert_eq!([5], *m);
+    }
+}
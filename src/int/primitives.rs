@@ -0,0 +1,360 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::ops;
+
+use super::magnitude::{inline_value, INLINE_LIMBS};
+use super::{add_signed, cmp_signed, divide, mul_signed, unwrap_division, Int, Rounding};
+use crate::backend::Native;
+use crate::errors::ConversionError;
+use crate::workspace::Workspace;
+use crate::IntLimb;
+
+// Primitives as an `Int` with an inline magnitude, so mixed operations don't allocate
+// a temporary.
+pub(super) trait ToSmall {
+    fn to_small(self) -> Int;
+}
+
+macro_rules! impl_to_small_signed {
+    ($($t:ident)*) => {$(
+        impl ToSmall for $t {
+            fn to_small(self) -> Int {
+                Int::small(if self < 0 { -1 } else { 1 }, self.unsigned_abs() as u128)
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_to_small_unsigned {
+    ($($t:ident)*) => {$(
+        impl ToSmall for $t {
+            fn to_small(self) -> Int {
+                Int::small(1, self as u128)
+            }
+        }
+    )*};
+}
+
+impl_to_small_signed!(i8 i16 i32 i64 i128 isize);
+impl_to_small_unsigned!(u8 u16 u32 u64 u128 usize);
+
+fn trunc_div(a_sign: i8, a: &[IntLimb], b_sign: i8, b: &[IntLimb]) -> (Int, Int) {
+    unwrap_division(divide::<Native>(
+        a_sign,
+        a,
+        b_sign,
+        b,
+        Rounding::Trunc,
+        &mut Workspace::new(),
+    ))
+}
+
+macro_rules! impl_primitive_ops {
+    ($($t:ident)*) => {$(
+        impl_op_ex!(+ |a: &Int, b: $t| -> Int {
+            let b = b.to_small();
+            add_signed(a, b.sign, &b.repr)
+        });
+
+        impl_op_ex!(+ |a: $t, b: &Int| -> Int {
+            let a = a.to_small();
+            add_signed(b, a.sign, &a.repr)
+        });
+
+        impl_op_ex!(- |a: &Int, b: $t| -> Int {
+            let b = b.to_small();
+            add_signed(a, -b.sign, &b.repr)
+        });
+
+        impl_op_ex!(- |a: $t, b: &Int| -> Int {
+            let a = a.to_small();
+            let mut r = add_signed(b, -a.sign, &a.repr);
+            r.sign = -r.sign;
+            r
+        });
+
+        impl_op_ex!(* |a: &Int, b: $t| -> Int {
+            let b = b.to_small();
+            mul_signed::<Native>(a, b.sign, &b.repr, &mut Workspace::new())
+        });
+
+        impl_op_ex!(* |a: $t, b: &Int| -> Int {
+            let a = a.to_small();
+            mul_signed::<Native>(b, a.sign, &a.repr, &mut Workspace::new())
+        });
+
+        impl_op_ex!(/ |a: &Int, b: $t| -> Int {
+            let b = b.to_small();
+            trunc_div(a.sign, &a.repr, b.sign, &b.repr).0
+        });
+
+        impl_op_ex!(/ |a: $t, b: &Int| -> Int {
+            let a = a.to_small();
+            trunc_div(a.sign, &a.repr, b.sign, &b.repr).0
+        });
+
+        impl_op_ex!(% |a: &Int, b: $t| -> Int {
+            let b = b.to_small();
+            trunc_div(a.sign, &a.repr, b.sign, &b.repr).1
+        });
+
+        impl_op_ex!(% |a: $t, b: &Int| -> Int {
+            let a = a.to_small();
+            trunc_div(a.sign, &a.repr, b.sign, &b.repr).1
+        });
+
+        // No `PartialEq<Int>` for the primitives themselves, it breaks type inference
+        // of unrelated comparisons such as `vec_of_usize == []`.
+        impl PartialEq<$t> for Int {
+            fn eq(&self, other: &$t) -> bool {
+                let other = other.to_small();
+                cmp_signed(self, other.sign, &other.repr) == Ordering::Equal
+            }
+        }
+
+        impl PartialOrd<$t> for Int {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                let other = other.to_small();
+                Some(cmp_signed(self, other.sign, &other.repr))
+            }
+        }
+    )*};
+}
+
+impl_primitive_ops!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+
+impl Int {
+    // Magnitude if it fits in 128 bits.
+    fn magnitude_u128(&self) -> Option<u128> {
+        inline_value(&self.repr)
+    }
+}
+
+macro_rules! impl_primitive_conversions {
+    ($($t:ident $wrapping:ident $saturating:ident)*) => {$(
+        impl From<$t> for Int {
+            fn from(value: $t) -> Int {
+                value.to_small()
+            }
+        }
+
+        impl TryFrom<&Int> for $t {
+            type Error = ConversionError;
+
+            fn try_from(value: &Int) -> Result<$t, ConversionError> {
+                let magnitude = value.magnitude_u128().ok_or(ConversionError::OutOfRange)?;
+
+                let converted = if value.sign < 0 {
+                    if magnitude > i128::MIN.unsigned_abs() {
+                        return Err(ConversionError::OutOfRange);
+                    }
+                    $t::try_from((magnitude as i128).wrapping_neg()).ok()
+                } else {
+                    $t::try_from(magnitude).ok()
+                };
+
+                converted.ok_or(ConversionError::OutOfRange)
+            }
+        }
+
+        impl TryFrom<Int> for $t {
+            type Error = ConversionError;
+
+            fn try_from(value: Int) -> Result<$t, ConversionError> {
+                $t::try_from(&value)
+            }
+        }
+
+        impl Int {
+            /// Converts keeping only the low bits of the two's complement value,
+            /// same as an `as` cast between primitives.
+            pub fn $wrapping(&self) -> $t {
+                let low_limbs = &self.repr[..usize::min(self.repr.len(), INLINE_LIMBS)];
+                let low = inline_value(low_limbs).unwrap();
+                let low = if self.sign < 0 { low.wrapping_neg() } else { low };
+
+                low as $t
+            }
+
+            /// Converts clamping values outside of the target range to its bounds.
+            pub fn $saturating(&self) -> $t {
+                match $t::try_from(self) {
+                    Ok(v) => v,
+                    Err(_) if self.sign < 0 => $t::MIN,
+                    Err(_) => $t::MAX,
+                }
+            }
+        }
+    )*};
+}
+
+impl_primitive_conversions!(
+    i8 to_i8_wrapping to_i8_saturating
+    i16 to_i16_wrapping to_i16_saturating
+    i32 to_i32_wrapping to_i32_saturating
+    i64 to_i64_wrapping to_i64_saturating
+    i128 to_i128_wrapping to_i128_saturating
+    isize to_isize_wrapping to_isize_saturating
+    u8 to_u8_wrapping to_u8_saturating
+    u16 to_u16_wrapping to_u16_saturating
+    u32 to_u32_wrapping to_u32_saturating
+    u64 to_u64_wrapping to_u64_saturating
+    u128 to_u128_wrapping to_u128_saturating
+    usize to_usize_wrapping to_usize_saturating
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(v: i128) -> Int {
+        Int::from((10, v.to_string().as_str()))
+    }
+
+    const VALUES: [i128; 9] = [
+        0,
+        1,
+        -1,
+        7,
+        -7,
+        u64::MAX as i128,
+        -(u64::MAX as i128) - 1,
+        0x1234_5678_9abc_def0_1234_5678,
+        -0x7fff_0000_ffff_0000_1234_5678,
+    ];
+
+    #[test]
+    fn i64_ops_both_orders() {
+        for &a in VALUES.iter() {
+            for &b in [0i64, 1, -1, 3, -3, i64::MAX, i64::MIN].iter() {
+                let (x, y) = (int(a), b as i128);
+
+                assert_eq!(int(a + y), &x + b, "{} + {}", a, b);
+                assert_eq!(int(y + a), b + &x, "{} + {}", b, a);
+                assert_eq!(int(a - y), &x - b, "{} - {}", a, b);
+                assert_eq!(int(y - a), b - &x, "{} - {}", b, a);
+                if let Some(p) = a.checked_mul(y) {
+                    assert_eq!(int(p), &x * b, "{} * {}", a, b);
+                    assert_eq!(int(p), b * &x, "{} * {}", b, a);
+                }
+
+                if b != 0 {
+                    assert_eq!(int(a / y), &x / b, "{} / {}", a, b);
+                    assert_eq!(int(a % y), &x % b, "{} % {}", a, b);
+                }
+                if a != 0 {
+                    assert_eq!(int(y / a), b / &x, "{} / {}", b, a);
+                    assert_eq!(int(y % a), b % &x, "{} % {}", b, a);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn u128_ops_use_two_limbs() {
+        let big = u128::MAX / 3;
+        let x = int(-5);
+
+        assert_eq!(
+            int(-5) + Int::from((10, big.to_string().as_str())),
+            &x + big
+        );
+        assert_eq!(
+            Int::from((10, "-567137278201564105772291012386280352425")),
+            x * big
+        );
+        assert_eq!(Int::zero(), int(5) / big);
+        assert_eq!(int(5), 5u128 % int(i64::MAX as i128));
+    }
+
+    #[test]
+    fn comparisons_with_primitives() {
+        for &a in VALUES.iter() {
+            let x = int(a);
+
+            for &b in [0i64, 1, -1, 7, -7, i64::MAX, i64::MIN].iter() {
+                assert_eq!(a == b as i128, x == b, "{} == {}", a, b);
+                assert_eq!(
+                    a.partial_cmp(&(b as i128)),
+                    x.partial_cmp(&b),
+                    "{} <=> {}",
+                    a,
+                    b
+                );
+            }
+
+            assert_eq!(a == u64::MAX as i128, x == u64::MAX);
+            assert_eq!(a < 7, x < 7u8);
+            assert_eq!(a > -1, x > -1isize);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Division by zero")]
+    fn division_by_zero_primitive() {
+        let _ = Int::one() / 0u32;
+    }
+
+    macro_rules! roundtrip {
+        ($($t:ident)*) => {$(
+            for &v in [$t::MIN, $t::MAX, 0, 1, $t::MAX / 3].iter() {
+                let i = Int::from(v);
+                assert_eq!(v.to_string(), i.to_string());
+                assert_eq!(Ok(v), $t::try_from(&i));
+            }
+
+            assert_eq!(
+                Err(ConversionError::OutOfRange),
+                $t::try_from(Int::from($t::MAX) + 1)
+            );
+            assert_eq!(
+                Err(ConversionError::OutOfRange),
+                $t::try_from(Int::from($t::MIN) - 1)
+            );
+        )*};
+    }
+
+    #[test]
+    fn primitive_roundtrip() {
+        roundtrip!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+    }
+
+    #[test]
+    fn try_from_large_value() {
+        let big = Int::from(u128::MAX) * Int::from(u128::MAX);
+
+        assert_eq!(Err(ConversionError::OutOfRange), u128::try_from(&big));
+        assert_eq!(Err(ConversionError::OutOfRange), i8::try_from(-big));
+    }
+
+    #[test]
+    fn wrapping_matches_as_cast() {
+        for &a in VALUES.iter() {
+            let x = int(a);
+
+            assert_eq!(a as u8, x.to_u8_wrapping());
+            assert_eq!(a as i16, x.to_i16_wrapping());
+            assert_eq!(a as u64, x.to_u64_wrapping());
+            assert_eq!(a as i64, x.to_i64_wrapping());
+            assert_eq!(a as u128, x.to_u128_wrapping());
+            assert_eq!(a, x.to_i128_wrapping());
+        }
+
+        let big = ((Int::from(u128::MAX) + 1u8) << 10) + 5u8;
+        assert_eq!(5, big.to_u64_wrapping());
+        assert_eq!(-5, (-big).to_i32_wrapping());
+    }
+
+    #[test]
+    fn saturating_clamps() {
+        assert_eq!(u8::MAX, int(300).to_u8_saturating());
+        assert_eq!(0, int(-300).to_u8_saturating());
+        assert_eq!(i8::MIN, int(-300).to_i8_saturating());
+        assert_eq!(-7, int(-7).to_i64_saturating());
+        assert_eq!(
+            u64::MAX,
+            int(0x1234_5678_9abc_def0_1234_5678).to_u64_saturating()
+        );
+        assert_eq!(i128::MIN, (-(Int::from(u128::MAX))).to_i128_saturating());
+    }
+}
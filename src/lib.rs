@@ -3,11 +3,14 @@ extern crate impl_ops;
 
 pub type IntLimb = usize;
 
+pub mod backend;
 pub mod errors;
 pub mod int;
+pub mod workspace;
 
 mod algorithms;
 mod asm_ops;
 mod base_ops;
+mod bit_ops;
 mod conversions;
 mod utils;
@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 
-use crate::asm_ops::cmp_slices;
+use crate::backend::LimbBackend;
 use crate::IntLimb;
 
 #[inline]
@@ -14,6 +14,13 @@ pub(crate) fn trim_zeros(x: &mut Vec<IntLimb>) {
     }
 }
 
+// `x` without its leading zero limbs.
+#[inline]
+pub(crate) fn trimmed(x: &[IntLimb]) -> &[IntLimb] {
+    let n = x.iter().rposition(|&limb| limb != 0).map_or(0, |i| i + 1);
+    &x[..n]
+}
+
 #[inline]
 pub(crate) fn bit_len(x: IntLimb) -> u32 {
     let mut i = 0;
@@ -27,37 +34,14 @@ pub(crate) fn bit_len(x: IntLimb) -> u32 {
 }
 
 #[inline]
-pub(crate) fn cmp_repr(left: &[IntLimb], right: &[IntLimb]) -> Ordering {
+pub(crate) fn cmp_repr<B: LimbBackend>(left: &[IntLimb], right: &[IntLimb]) -> Ordering {
     let (n, m) = (left.len(), right.len());
 
     match n.cmp(&m) {
-        Ordering::Less => return Ordering::Less,
-        Ordering::Greater => return Ordering::Greater,
-        Ordering::Equal => {
-            let cmp_res = unsafe { cmp_slices(left.as_ptr(), right.as_ptr(), n as IntLimb) };
-            match cmp_res {
-                -1 => return Ordering::Less,
-                0 => return Ordering::Equal,
-                1 => return Ordering::Greater,
-                _ => panic!("Unexpected result"),
-            }
-        }
-    }
-}
-
-#[inline]
-pub(crate) fn new_repr(value: IntLimb, base: IntLimb) -> Vec<IntLimb> {
-    let mut repr = Vec::new();
-    let mut value = value;
-    while value >= base {
-        repr.push(value % base);
-        value /= base;
+        Ordering::Less => Ordering::Less,
+        Ordering::Greater => Ordering::Greater,
+        Ordering::Equal => B::cmp(left, right),
     }
-    if value > 0 {
-        repr.push(value);
-    }
-
-    repr
 }
 
 #[inline]
@@ -68,6 +52,7 @@ pub(crate) fn internal_repr(value: IntLimb) -> Vec<IntLimb> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::Native;
 
     #[test]
     fn trim_zeros_empty_vec() {
@@ -125,27 +110,27 @@ mod tests {
 
         assert_eq!(
             test_inputs.0 .2,
-            cmp_repr(&test_inputs.0 .0, &test_inputs.0 .1)
+            cmp_repr::<Native>(&test_inputs.0 .0, &test_inputs.0 .1)
         );
         assert_eq!(
             test_inputs.1 .2,
-            cmp_repr(&test_inputs.1 .0, &test_inputs.1 .1)
+            cmp_repr::<Native>(&test_inputs.1 .0, &test_inputs.1 .1)
         );
         assert_eq!(
             test_inputs.2 .2,
-            cmp_repr(&test_inputs.2 .0, &test_inputs.2 .1)
+            cmp_repr::<Native>(&test_inputs.2 .0, &test_inputs.2 .1)
         );
         assert_eq!(
             test_inputs.3 .2,
-            cmp_repr(&test_inputs.3 .0, &test_inputs.3 .1)
+            cmp_repr::<Native>(&test_inputs.3 .0, &test_inputs.3 .1)
         );
         assert_eq!(
             test_inputs.4 .2,
-            cmp_repr(&test_inputs.4 .0, &test_inputs.4 .1)
+            cmp_repr::<Native>(&test_inputs.4 .0, &test_inputs.4 .1)
         );
         assert_eq!(
             test_inputs.5 .2,
-            cmp_repr(&test_inputs.5 .0, &test_inputs.5 .1)
+            cmp_repr::<Native>(&test_inputs.5 .0, &test_inputs.5 .1)
         );
     }
 }
@@ -0,0 +1,102 @@
+//! Reusable scratch memory for multiplication and division.
+//!
+//! Karatsuba multiplication and squaring and the schoolbook division take their
+//! temporaries from one buffer sized up front instead of allocating on every recursion
+//! level or division step. That covers the Karatsuba products under Toom-3, Toom-4 and
+//! the recursive divisions too, but not their own intermediate values: Toom evaluation
+//! and interpolation, the NTT and the blocks of Burnikel-Ziegler and Newton division
+//! still allocate. Passing the
+//! same [`Workspace`] to repeated calls, e.g. [`Int::mul_with`](crate::int::Int::mul_with),
+//! reuses the buffer too.
+
+use crate::base_ops::{div_scratch_size, mul_scratch_size, sqr_scratch_size};
+use crate::IntLimb;
+
+/// Limbs of scratch space needed to multiply, square or divide operands of `n` and `m` limbs.
+pub fn scratch_size(n: usize, m: usize) -> usize {
+    let sqr = sqr_scratch_size(usize::max(n, m));
+
+    usize::max(
+        sqr,
+        usize::max(mul_scratch_size(n, m), div_scratch_size(n, m)),
+    )
+}
+
+/// Scratch buffer the arithmetic borrows its temporaries from, it grows to the largest
+/// size requested and keeps it for later calls.
+#[derive(Clone, Debug, Default)]
+pub struct Workspace {
+    scratch: Vec<IntLimb>,
+}
+
+impl Workspace {
+    /// Empty workspace, it allocates on first use.
+    pub fn new() -> Workspace {
+        Workspace::default()
+    }
+
+    /// Workspace whose buffer doesn't have to grow for operands of up to `n` and `m` limbs.
+    pub fn with_capacity(n: usize, m: usize) -> Workspace {
+        Workspace {
+            scratch: vec![0; scratch_size(n, m)],
+        }
+    }
+
+    /// Limbs of scratch space currently held.
+    pub fn capacity(&self) -> usize {
+        self.scratch.len()
+    }
+
+    // At least `size` limbs with arbitrary contents.
+    pub(crate) fn scratch(&mut self, size: usize) -> &mut [IntLimb] {
+        if self.scratch.len() < size {
+            self.scratch.resize(size, 0);
+        }
+
+        &mut self.scratch[..size]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::Native;
+    use crate::base_ops::{div_with, mul_with, sqr_with};
+
+    #[test]
+    fn scratch_grows_and_stays() {
+        let mut workspace = Workspace::new();
+        assert_eq!(0, workspace.capacity());
+
+        assert_eq!(10, workspace.scratch(10).len());
+        assert_eq!(4, workspace.scratch(4).len());
+        assert_eq!(10, workspace.capacity());
+    }
+
+    #[test]
+    fn with_capacity_covers_scratch_size() {
+        for &(n, m) in [(1, 1), (20, 20), (100, 37), (37, 100), (149, 149)].iter() {
+            assert_eq!(
+                scratch_size(n, m),
+                Workspace::with_capacity(n, m).capacity()
+            );
+        }
+        assert!(scratch_size(100, 100) >= scratch_size(50, 50));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn with_capacity_does_not_grow() {
+        for &(n, m) in [(40, 30), (300, 250), (700, 690), (1500, 400), (2000, 1990)].iter() {
+            let a = vec![IntLimb::MAX; n];
+            let b = vec![IntLimb::MAX - 1; m];
+            let mut workspace = Workspace::with_capacity(n, m);
+            let capacity = workspace.capacity();
+
+            mul_with::<Native>(&a, &b, &mut workspace);
+            sqr_with::<Native>(&a, &mut workspace);
+            div_with::<Native>(&a, &b, &mut workspace).unwrap();
+            assert_eq!(capacity, workspace.capacity(), "{} x {}", n, m);
+        }
+    }
+}